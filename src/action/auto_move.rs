@@ -0,0 +1,168 @@
+use crate::board::direction::DIRECTIONS;
+use crate::creature::{visible_creatures, Explored};
+
+use super::command::{Command, Travel};
+use super::*;
+
+// Auto-movement
+//
+// exploring and running repeat a step until something interesting happens.
+// Each step is planned as the previous one completes, so anything new - a
+// junction, a door, an item, a creature coming into view - can stop it.
+
+// stop eventually, even in a very long corridor
+const MAX_RUN_STEPS: u32 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AutoMoveMode {
+    Explore,
+    Run(Dir),
+}
+
+#[derive(Component, Debug, Clone)]
+pub(crate) struct AutoMove {
+    pub(crate) mode: AutoMoveMode,
+    // creatures already in view when we set off; anyone else showing up stops us
+    seen: HashSet<Entity>,
+    steps: u32,
+    // whether the cells either side were open, on the last step of a run
+    sides: Option<[bool; 2]>,
+}
+
+impl AutoMove {
+    pub(crate) fn new(mode: AutoMoveMode, seen: HashSet<Entity>) -> Self {
+        AutoMove {
+            mode,
+            seen,
+            steps: 0,
+            sides: None,
+        }
+    }
+}
+
+// follow a corridor around its bends; stop at junctions, openings and dead ends.
+// On open ground, keep going straight until the walls either side change.
+fn next_run_step(
+    board: &Board,
+    pos: &IVec3,
+    dir: Dir,
+    sides: &mut Option<[bool; 2]>,
+) -> Option<Dir> {
+    let open = |d: Dir| board.terrain_step_cost(pos, d).is_some();
+
+    let now = [
+        open(dir.counter_clockwise_neigbour(2)),
+        open(dir.clockwise_neighbour(2)),
+    ];
+    let changed = sides.replace(now).is_some_and(|before| before != now);
+
+    let forward: Vec<Dir> = [
+        dir,
+        dir.counter_clockwise_neigbour(1),
+        dir.clockwise_neighbour(1),
+        dir.counter_clockwise_neigbour(2),
+        dir.clockwise_neighbour(2),
+    ]
+    .into_iter()
+    .filter(|d| open(*d))
+    .collect();
+
+    match forward.as_slice() {
+        [] => None,
+        [only] => Some(*only),
+        _ if open(dir) && !changed => Some(dir),
+        _ => None,
+    }
+}
+
+fn something_here(board: &Board, pos: &IVec3) -> bool {
+    board.items_store.get(pos).is_some()
+        || DIRECTIONS
+            .iter()
+            .any(|dir| board.feature_store.get(&(*pos + dir.offset())).is_some())
+}
+
+pub(crate) fn continue_auto_move(
+    mut commands: Commands,
+    mut ev_added: EventWriter<ActionAddedEvent>,
+    mut query: Query<(Entity, &mut Actor, &mut AutoMove, &Locus, &Explored), Without<ActorAction>>,
+    board: Res<Board>,
+) {
+    for (entity, mut actor, mut auto, locus, explored) in query.iter_mut() {
+        if !actor.queue.is_empty() {
+            continue;
+        }
+        let pos = locus.position;
+        let mode = auto.mode;
+
+        match mode {
+            AutoMoveMode::Explore => {
+                let route = explored
+                    .frontier(&board, pos)
+                    .find(|cell| *cell != pos)
+                    .and_then(|dest| {
+                        let actions = Command::TravelTo(dest).expand(entity, pos, &board)?;
+                        Some((dest, actions))
+                    });
+
+                if let Some((dest, mut actions)) = route {
+                    if let Some(first) = actions.pop_front() {
+                        actor.queue = actions;
+                        commands
+                            .entity(entity)
+                            .insert((ActorAction(first), Travel::new(Command::TravelTo(dest))));
+                        ev_added.send(ActionAddedEvent { entity });
+                        continue;
+                    }
+                }
+                info!("nothing left to explore");
+            }
+            AutoMoveMode::Run(dir) => {
+                let AutoMove { steps, sides, .. } = &mut *auto;
+                let interesting = *steps > 0 && something_here(&board, &pos);
+
+                if !interesting && *steps < MAX_RUN_STEPS {
+                    if let Some(next) = next_run_step(&board, &pos, dir, sides) {
+                        *steps += 1;
+                        auto.mode = AutoMoveMode::Run(next);
+                        commands
+                            .entity(entity)
+                            .insert(ActorAction(Action::walk(entity, next)));
+                        ev_added.send(ActionAddedEvent { entity });
+                        continue;
+                    }
+                }
+            }
+        }
+        commands.entity(entity).remove::<AutoMove>();
+    }
+}
+
+// anyone new coming into view stops it, once the step under way is done;
+// nothing's been aborted, so there's nothing to tell anyone about
+pub(crate) fn interrupt_auto_move(
+    mut query: Query<(Entity, &mut Actor, &AutoMove, &Vision)>,
+    mut commands: Commands,
+    board: Res<Board>,
+) {
+    for (entity, mut actor, auto, vision) in query.iter_mut() {
+        if !visible_creatures(vision, &board, entity).is_subset(&auto.seen) {
+            info!("something comes into view");
+            actor.clear_queue();
+            commands.entity(entity).remove::<(AutoMove, Travel)>();
+        }
+    }
+}
+
+pub(crate) fn stop_auto_move(
+    mut ev_abort: EventReader<ActionAbortedEvent>,
+    mut commands: Commands,
+    mut query: Query<&mut Actor, With<AutoMove>>,
+) {
+    for ActionAbortedEvent { entity, .. } in ev_abort.read() {
+        if let Ok(mut actor) = query.get_mut(*entity) {
+            actor.clear_queue();
+            commands.entity(*entity).remove::<(AutoMove, Travel)>();
+        }
+    }
+}
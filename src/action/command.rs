@@ -0,0 +1,298 @@
+use crate::board::pathfinding::{astar, path_directions};
+use crate::creature::visible_creatures;
+
+use super::auto_move::AutoMove;
+use super::kinds::hold::{Response, Trigger};
+use super::kinds::wait::Until;
+
+use super::*;
+
+// Commands
+//
+// a command is an instruction which expands into a series of actions, queued up
+// on the Actor - eg. travelling somewhere becomes a walk for each step along the way.
+
+// how many times to look for another way around before giving up
+const MAX_REPLANS: u8 = 3;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Command {
+    Walk(Dir),
+    Run(Dir),
+    Turn(Dir),
+    TravelTo(IVec3),
+    // close to striking distance, then attack
+    Attack(Entity),
+    // stand ready to respond
+    Hold(Trigger, Response),
+    // for a while
+    Wait(Dur),
+    // until recovered
+    Rest,
+    // until something happens
+    Watch,
+    // get down, or back up
+    Stance(Stance),
+}
+
+impl Command {
+    pub(crate) fn expand(
+        &self,
+        entity: Entity,
+        from: IVec3,
+        board: &Board,
+    ) -> Option<VecDeque<Action>> {
+        match *self {
+            Command::Walk(dir) => Some(VecDeque::from([Action::walk(entity, dir)])),
+            Command::Run(dir) => Some(VecDeque::from([Action::run(entity, dir)])),
+            Command::Turn(dir) => Some(VecDeque::from([Action::turn(entity, dir)])),
+            Command::TravelTo(destination) => {
+                let path = astar(board, from, destination, Some(entity))?;
+                Some(walks(entity, from, &path))
+            }
+            Command::Attack(target) => {
+                let destination = *board.creature_store.pos_for(&target)?;
+                let mut path = astar(board, from, destination, Some(entity))?;
+                // stop alongside them
+                path.pop();
+                let mut actions = walks(entity, from, &path);
+                actions.push_back(Action::attack(entity, target));
+                Some(actions)
+            }
+            Command::Wait(duration) => Some(VecDeque::from([Action::wait(entity, duration)])),
+            Command::Rest => Some(VecDeque::from([Action::wait_until(entity, Until::Rested)])),
+            Command::Watch => Some(VecDeque::from([Action::wait_until(
+                entity,
+                Until::Disturbed,
+            )])),
+            Command::Stance(stance) => {
+                Some(VecDeque::from([Action::change_stance(entity, stance)]))
+            }
+            Command::Hold(trigger, response) => {
+                Some(VecDeque::from([Action::hold(entity, trigger, response)]))
+            }
+        }
+    }
+}
+
+impl Command {
+    // whether it follows a route, which can be found again if the way is blocked
+    fn follows_route(&self) -> bool {
+        matches!(self, Command::TravelTo(_) | Command::Attack(_))
+    }
+}
+
+fn walks(entity: Entity, from: IVec3, path: &[IVec3]) -> VecDeque<Action> {
+    path_directions(from, path)
+        .into_iter()
+        .map(|dir| Action::walk(entity, dir))
+        .collect()
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct CommandEvent {
+    pub(crate) entity: Entity,
+    pub(crate) command: Command,
+}
+
+// present while an actor is following a path, so it can find another if a step is blocked
+#[derive(Component, Debug, PartialEq, Clone, Copy)]
+pub(crate) struct Travel {
+    pub(crate) command: Command,
+    replans: u8,
+}
+
+impl Travel {
+    pub(crate) fn new(command: Command) -> Self {
+        Travel {
+            command,
+            replans: 0,
+        }
+    }
+
+    // refill the actor's queue with a fresh path from where it stands, returning the first step
+    pub(crate) fn replan(
+        &mut self,
+        entity: Entity,
+        from: IVec3,
+        board: &Board,
+        actor: &mut Actor,
+    ) -> Option<Action> {
+        if self.replans >= MAX_REPLANS {
+            return None;
+        }
+        self.replans += 1;
+
+        let mut actions = self.command.expand(entity, from, board)?;
+        let first = actions.pop_front()?;
+        actor.queue = actions;
+        Some(first)
+    }
+}
+
+pub(crate) fn issue_commands(
+    mut ev_command: EventReader<CommandEvent>,
+    mut ev_added: EventWriter<ActionAddedEvent>,
+    mut commands: Commands,
+    mut query: Query<(&mut Actor, &Locus), Without<ActorAction>>,
+    board: Res<Board>,
+) {
+    for CommandEvent { entity, command } in ev_command.read() {
+        let Ok((mut actor, locus)) = query.get_mut(*entity) else {
+            warn!("ignoring command for busy or missing actor: {:?}", command);
+            continue;
+        };
+
+        let Some(mut actions) = command.expand(*entity, locus.position, &board) else {
+            warn!("no way to carry out command: {:?}", command);
+            continue;
+        };
+
+        let Some(first) = actions.pop_front() else {
+            continue;
+        };
+
+        actor.queue = actions;
+        let mut cmds = commands.entity(*entity);
+        cmds.insert(ActorAction(first));
+        if command.follows_route() {
+            cmds.insert(Travel::new(*command));
+        }
+        ev_added.send(ActionAddedEvent { entity: *entity });
+    }
+}
+
+// Command queue
+//
+// the player can line up several commands - optionally repeated - which are
+// issued one after another as each is carried out. Anything new coming into
+// view calls the rest off.
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) struct QueuedCommand {
+    pub(crate) command: Command,
+    pub(crate) repeat: u32,
+}
+
+#[derive(Component, Debug, Clone, Default)]
+pub(crate) struct CommandQueue {
+    pub(crate) pending: VecDeque<QueuedCommand>,
+    // a repeat count being typed in, for the next command
+    pub(crate) count: Option<u32>,
+    // creatures already in view, so only new arrivals interrupt
+    seen: HashSet<Entity>,
+}
+
+impl CommandQueue {
+    pub(crate) fn push(&mut self, command: Command) {
+        let repeat = self.count.take().unwrap_or(1).max(1);
+        self.pending.push_back(QueuedCommand { command, repeat });
+    }
+
+    pub(crate) fn push_digit(&mut self, digit: u32) {
+        let count = self
+            .count
+            .unwrap_or(0)
+            .saturating_mul(10)
+            .saturating_add(digit);
+        self.count = Some(count);
+    }
+
+    pub(crate) fn next(&mut self) -> Option<Command> {
+        let front = self.pending.front_mut()?;
+        let command = front.command;
+        front.repeat -= 1;
+        if front.repeat == 0 {
+            self.pending.pop_front();
+        }
+        Some(command)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.pending.clear();
+        self.count = None;
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        let mut parts: Vec<String> = self
+            .pending
+            .iter()
+            .map(|q| match q.repeat {
+                1 => format!("{:?}", q.command),
+                n => format!("{} x {:?}", n, q.command),
+            })
+            .collect();
+        if let Some(count) = self.count {
+            parts.push(format!("{} x ...", count));
+        }
+        parts.join(", then ")
+    }
+}
+
+// give up on everything still to do, finishing the current step
+pub(crate) fn cancel_commands(
+    commands: &mut Commands,
+    entity: Entity,
+    queue: &mut CommandQueue,
+    actor: &mut Actor,
+) {
+    queue.clear();
+    actor.clear_queue();
+    commands.entity(entity).remove::<(Travel, AutoMove)>();
+}
+
+// hand out the next command once the last one has been carried out
+pub(crate) fn dispatch_queued_commands(
+    mut query: Query<
+        (Entity, &Actor, &mut CommandQueue),
+        (Without<ActorAction>, Without<AutoMove>),
+    >,
+    mut ev_command: EventWriter<CommandEvent>,
+) {
+    for (entity, actor, mut queue) in query.iter_mut() {
+        if !actor.queue.is_empty() {
+            continue;
+        }
+        if let Some(command) = queue.next() {
+            ev_command.send(CommandEvent { entity, command });
+        }
+    }
+}
+
+pub(crate) fn interrupt_queued_commands(
+    mut commands: Commands,
+    mut query: Query<(Entity, &Vision, &mut Actor, &mut CommandQueue)>,
+    board: Res<Board>,
+) {
+    for (entity, vision, mut actor, mut queue) in query.iter_mut() {
+        let visible = visible_creatures(vision, &board, entity);
+        let busy = !queue.is_empty() || !actor.queue.is_empty();
+
+        if busy && !visible.is_subset(&queue.seen) {
+            info!("something comes into view; calling off queued commands");
+            cancel_commands(&mut commands, entity, &mut queue, &mut actor);
+        }
+        queue.seen = visible;
+    }
+}
+
+#[test]
+fn test_command_queue_repeats() {
+    let mut queue = CommandQueue::default();
+    queue.push_digit(1);
+    queue.push_digit(2);
+    queue.push(Command::Walk(Dir::East));
+    queue.push(Command::Turn(Dir::North));
+
+    assert_eq!(queue.pending[0].repeat, 12);
+    assert_eq!(queue.count, None);
+    for _ in 0..12 {
+        assert_eq!(queue.next(), Some(Command::Walk(Dir::East)));
+    }
+    assert_eq!(queue.next(), Some(Command::Turn(Dir::North)));
+    assert_eq!(queue.next(), None);
+}
@@ -0,0 +1,214 @@
+use crate::board::pathfinding::STRAIGHT_COST;
+use crate::creature::{Approach, Attributes, Condition, ConditionList, PaceId, Stance};
+
+use super::momentum;
+use super::*;
+
+// Action durations
+//
+// worked out when an action is validated, from who's doing it and where:
+// their pace and stride, how quick they are on their feet, how low they're
+// keeping, what they're carrying, the ground underfoot, how much care they're
+// taking, and anything ailing them.
+
+// moving takes 1 second at default pace
+const DEFAULT_MOVE_TICKS: f32 = 10.;
+// each point of speed either side of average
+const SPEED_FACTOR: f32 = 0.05;
+const AVERAGE_SPEED: f32 = 5.5;
+// carrying up to this much goes unnoticed
+const UNHINDERED_LOAD: u8 = 50;
+
+// the components a Mobility is worked out from
+pub(crate) type MobilityQuery = (
+    &'static Pace,
+    &'static Attributes,
+    &'static Approach,
+    &'static ConditionList,
+    &'static Stance,
+);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Mobility {
+    pace: Pace,
+    speed: u8,
+    stride: f64,
+    approach: Approach,
+    stance: Stance,
+    hindrance: f32,
+}
+
+impl Mobility {
+    pub(crate) fn new(
+        pace: &Pace,
+        attributes: &Attributes,
+        approach: &Approach,
+        conditions: &ConditionList,
+        stance: &Stance,
+    ) -> Self {
+        Mobility {
+            pace: *pace,
+            speed: attributes.primary.speed,
+            stride: attributes.secondary.stride,
+            approach: *approach,
+            stance: *stance,
+            hindrance: hindrance(conditions),
+        }
+    }
+
+    pub(crate) fn stance(&self) -> Stance {
+        self.stance
+    }
+
+    fn quickness(&self) -> f32 {
+        1.0 - (self.speed as f32 - AVERAGE_SPEED) * SPEED_FACTOR
+    }
+
+    // ticks to cross a cell of open ground at the given pace
+    pub(crate) fn ticks_per_cell(&self, pace: &Pace) -> f32 {
+        let relaxed = Pace::get(PaceId::Relaxed).ticks_per_cell() as f32;
+        let base = pace.ticks_per_cell() as f32 * DEFAULT_MOVE_TICKS / relaxed;
+
+        base * self.quickness()
+            * approach_factor(&self.approach)
+            * self.stance.move_factor()
+            * self.hindrance
+            / self.stride as f32
+    }
+
+    pub(crate) fn move_duration(
+        &self,
+        movement: &MovementActionDetail,
+        board: &Board,
+        from: &IVec3,
+    ) -> Dur {
+        let Some(pace) = self.pace_for(movement) else {
+            return TURN_DURATION;
+        };
+        // diagonals and rough ground cost more
+        let step = board
+            .terrain_step_cost(from, *movement.direction())
+            .unwrap_or(STRAIGHT_COST);
+
+        to_ticks(self.ticks_per_cell(&pace) * step as f32 / STRAIGHT_COST as f32)
+    }
+
+    // as above, but getting up to speed - or checking a turn - on the way
+    pub(crate) fn stride_duration(
+        &self,
+        movement: &MovementActionDetail,
+        board: &Board,
+        locus: &Locus,
+    ) -> Dur {
+        let full_speed = self.move_duration(movement, board, &locus.position);
+        let Some(pace) = self.pace_for(movement) else {
+            return full_speed;
+        };
+        let top_ticks = self.ticks_per_cell(&pace);
+        let dir = *movement.direction();
+        let speed = momentum::stride_speed(locus, dir, &pace, top_ticks);
+
+        to_ticks(full_speed.as_u32() as f32 / speed)
+            + Dur::ticks(momentum::turn_ticks(locus, dir, top_ticks))
+    }
+
+    // ticks per cell once up to speed, for anything but a turn
+    pub(crate) fn full_speed_ticks(&self, movement: &MovementActionDetail) -> Option<f32> {
+        self.pace_for(movement)
+            .map(|pace| self.ticks_per_cell(&pace))
+    }
+
+    fn pace_for(&self, movement: &MovementActionDetail) -> Option<Pace> {
+        match movement {
+            MovementActionDetail::Turn(_) => None,
+            MovementActionDetail::Walk(_) => Some(self.pace),
+            MovementActionDetail::Run(_) => Some(Pace::get(PaceId::Running)),
+        }
+    }
+
+    // getting down, or up; care doesn't come into it
+    pub(crate) fn stance_duration(&self, to: Stance) -> Dur {
+        to_ticks(self.stance.change_ticks(to) as f32 * self.quickness() * self.hindrance)
+    }
+
+    // standing about for as long as a step would take
+    pub(crate) fn wait_duration(&self) -> Dur {
+        to_ticks(self.ticks_per_cell(&self.pace))
+    }
+}
+
+fn to_ticks(ticks: f32) -> Dur {
+    // float to int casts saturate, so an immobile creature just takes forever
+    Dur::ticks((ticks.round() as u32).max(1))
+}
+
+fn approach_factor(approach: &Approach) -> f32 {
+    match approach {
+        Approach::Painstaking => 2.0,
+        Approach::Careful => 1.5,
+        Approach::Focused => 1.1,
+        Approach::Casual => 1.0,
+        Approach::Hurried => 0.85,
+        Approach::Desperate => 0.75,
+    }
+}
+
+// how much conditions and load slow a creature down
+fn hindrance(conditions: &ConditionList) -> f32 {
+    let slowed_by = [
+        (Condition::Entangled, 2.0),
+        (Condition::Restrained, 3.0),
+        (Condition::Exhausted, 1.5),
+        (Condition::Tired, 1.1),
+        (Condition::Pained, 1.25),
+        (Condition::Bleeding, 1.1),
+        (Condition::Dizzy, 1.25),
+        (Condition::Concussed, 1.25),
+        (Condition::Intoxicated, 1.2),
+        (Condition::Sick, 1.15),
+    ];
+    let ailing: f32 = slowed_by
+        .iter()
+        .filter(|(condition, _)| conditions.has(*condition))
+        .map(|(_, factor)| factor)
+        .product();
+
+    let load = conditions.encumbrance().saturating_sub(UNHINDERED_LOAD) as f32 / 100.;
+
+    ailing * (1.0 + load)
+}
+
+#[test]
+fn test_fast_dwarves_move_fast() {
+    let board = Board::default();
+    let from = IVec3::new(5, 5, 0);
+    let walk = MovementActionDetail::Walk(Dir::East);
+
+    let mut attributes = Attributes::new();
+    let mut conditions = ConditionList::default();
+    let approach = Approach::default();
+    let pace = Pace::default();
+    let stance = Stance::default();
+
+    attributes.primary.speed = 5;
+    let average = Mobility::new(&pace, &attributes, &approach, &conditions, &stance);
+    attributes.primary.speed = 10;
+    let fast = Mobility::new(&pace, &attributes, &approach, &conditions, &stance);
+    conditions.add(Condition::Pained);
+    let wounded = Mobility::new(&pace, &attributes, &approach, &conditions, &stance);
+
+    let ticks = |m: &Mobility| m.move_duration(&walk, &board, &from);
+    assert_eq!(ticks(&average), Dur::ticks(10));
+    assert!(ticks(&fast) < ticks(&average));
+    assert!(ticks(&wounded) > ticks(&fast));
+
+    let prone = Mobility::new(&pace, &attributes, &approach, &conditions, &Stance::Prone);
+    assert!(ticks(&prone) > ticks(&wounded));
+
+    let diagonal = MovementActionDetail::Walk(Dir::NorthEast);
+    assert!(average.move_duration(&diagonal, &board, &from) > ticks(&average));
+    assert_eq!(
+        average.move_duration(&MovementActionDetail::Turn(Dir::South), &board, &from),
+        TURN_DURATION
+    );
+}
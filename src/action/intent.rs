@@ -0,0 +1,189 @@
+use crate::creature::Attributes;
+
+use super::kind::ActionKinds;
+use super::kinds::wait::Until;
+use super::*;
+
+// Intent
+//
+// reading what an opponent is about to do is most of what separates a
+// veteran from a corpse. Whenever someone in sight starts an action, or moves
+// into a new phase of one, each observer makes an opposed check - their
+// perception and notice against the actor's skill at whatever they're doing -
+// and learns more the better they do, from "moving" up to "wind up (attacking
+// you), 1s left".
+//
+// what each creature has read is kept on it, so AI can make use of it just
+// as the player does.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Insight {
+    // what sort of thing they're doing
+    Glimpse,
+    // and in which direction, or at whom
+    Clear,
+    // and which part of it they're in
+    Detailed,
+    // and how long it has left to run
+    Exact,
+}
+
+// how much a margin of success buys; things at the edge of vision can only
+// be glimpsed however sharp the observer
+pub(crate) fn insight(margin: i32, peripheral: bool) -> Insight {
+    let insight = match margin {
+        i32::MIN..=-1 => Insight::Glimpse,
+        0..=3 => Insight::Clear,
+        4..=7 => Insight::Detailed,
+        _ => Insight::Exact,
+    };
+    if peripheral {
+        insight.min(Insight::Glimpse)
+    } else {
+        insight
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Intent {
+    pub(crate) insight: Insight,
+    pub(crate) detail: ActionDetail,
+    pub(crate) phase: Option<&'static str>,
+    pub(crate) left: Dur,
+    // the action and phase this was read from; a new check when either changes
+    key: (u32, Option<&'static str>),
+}
+
+impl Intent {
+    pub(crate) fn describe(&self, observer: Entity) -> String {
+        let mut text = match self.detail {
+            ActionDetail::Move(MovementActionDetail::Turn(_)) => "turning",
+            ActionDetail::Move(MovementActionDetail::Walk(_)) => "moving",
+            ActionDetail::Move(MovementActionDetail::Run(_)) => "running",
+            ActionDetail::Attack(_) => "attacking",
+            ActionDetail::Shoot(_) => "shooting",
+            ActionDetail::Wait(Until::Rested) => "resting",
+            ActionDetail::Wait(_) => "waiting",
+            ActionDetail::Hold(_) => "ready",
+            ActionDetail::Stance(_) => "shifting",
+            ActionDetail::Inventory(_) => "busy",
+        }
+        .to_string();
+
+        if self.insight >= Insight::Clear {
+            if let ActionDetail::Move(x) = self.detail {
+                text = format!("{} {:?}", text, x.direction()).to_lowercase();
+            } else if let Some(target) = self.detail.target() {
+                text = match target == observer {
+                    true => format!("{} you", text),
+                    false => format!("{} someone else", text),
+                };
+            }
+        }
+
+        if self.insight >= Insight::Detailed {
+            if let Some(phase) = self.phase {
+                text = format!("{} ({})", phase, text);
+            }
+        }
+
+        if self.insight >= Insight::Exact {
+            text = format!("{}, {} left", text, self.left);
+        }
+
+        text
+    }
+}
+
+// what an observer has made of everyone else's current action
+#[derive(Component, Debug, Clone, Default)]
+pub(crate) struct Intents {
+    pub(crate) known: HashMap<Entity, Intent>,
+}
+
+impl Intents {
+    pub(crate) fn of(&self, actor: Entity) -> Option<&Intent> {
+        self.known.get(&actor)
+    }
+}
+
+// the skill which makes an action hard to read
+fn concealing_skill(detail: &ActionDetail, skills: &SkillList) -> u8 {
+    match detail {
+        ActionDetail::Attack(_) => skills.melee,
+        ActionDetail::Shoot(_) => skills.bow,
+        ActionDetail::Move(_) => skills.sneak,
+        _ => 0,
+    }
+}
+
+pub(crate) fn read_intents(
+    mut observers: Query<(Entity, &Vision, &Attributes, &SkillList, &mut Intents)>,
+    actors: Query<(Entity, &ActorAction, &Locus, &SkillList, &Stance)>,
+    kinds: Res<ActionKinds>,
+    tick: Res<TickCount>,
+    mut global_rng: ResMut<GlobalChaChaRng>,
+) {
+    let mut rng = RngComponent::from(&mut global_rng);
+
+    for (observer, vision, attributes, skills, mut intents) in observers.iter_mut() {
+        let mut seen: HashSet<Entity> = HashSet::new();
+
+        for (actor, ActorAction(action), locus, actor_skills, stance) in actors.iter() {
+            let ActionStatus::Active {
+                start_tick,
+                complete_tick,
+            } = action.status
+            else {
+                continue;
+            };
+            if actor == observer || !vision.can_see(&locus.position) {
+                continue;
+            }
+            seen.insert(actor);
+
+            let phase = action.current_phase(tick.0, &kinds).map(|p| p.name);
+            let left = Dur::between(tick.0, complete_tick);
+            let key = (start_tick, phase);
+
+            if let Some(intent) = intents.known.get_mut(&actor) {
+                if intent.key == key {
+                    intent.left = left;
+                    continue;
+                }
+            }
+
+            // easier to make out someone standing tall than lying flat
+            let watch = attributes.primary.perception as i32
+                + skills.notice as i32
+                + stance.conspicuity()
+                + rng.i32(1..=10);
+            let hide = concealing_skill(&action.detail, actor_skills) as i32 + rng.i32(1..=10);
+            let peripheral = !vision.focus.contains(&locus.position);
+
+            intents.known.insert(
+                actor,
+                Intent {
+                    insight: insight(watch - hide, peripheral),
+                    detail: action.detail,
+                    phase,
+                    left,
+                    key,
+                },
+            );
+        }
+
+        // out of sight, or no longer doing anything
+        intents.known.retain(|actor, _| seen.contains(actor));
+    }
+}
+
+#[test]
+fn test_insight_from_margin() {
+    assert_eq!(insight(-3, false), Insight::Glimpse);
+    assert_eq!(insight(0, false), Insight::Clear);
+    assert_eq!(insight(5, false), Insight::Detailed);
+    assert_eq!(insight(12, false), Insight::Exact);
+    // nobody reads a blade from the corner of their eye
+    assert_eq!(insight(12, true), Insight::Glimpse);
+}
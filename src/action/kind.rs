@@ -0,0 +1,139 @@
+use crate::creature::{Attributes, Stamina};
+
+use super::duration::{Mobility, MobilityQuery};
+use super::phase::Phase;
+use super::*;
+
+// Action kinds
+//
+// each kind of action - moving, attacking, shooting, and whatever verbs come
+// later - says for itself what it needs to go ahead, how long it takes, what
+// phases it has and what happens when it's done. A plugin registers the kind,
+// which wires up its validation; effects are ordinary systems in the
+// ActionKindSet::Effect set, looking for completed actions of their kind.
+
+pub(crate) trait ActionKind: Debug + Copy + Send + Sync + 'static {
+    // picks this kind's details out of an action, if it's one of ours
+    fn from_detail(detail: &ActionDetail) -> Option<Self>;
+
+    // preconditions, checked when the action comes up
+    fn validate(&self, actor: &ActorView, board: &Board) -> bool;
+
+    // None keeps the duration the action was created with
+    fn duration(&self, _actor: &ActorView, _board: &Board) -> Option<Dur> {
+        None
+    }
+
+    // an empty list means a single, uninterruptible phase
+    fn phases(&self) -> &'static [Phase] {
+        &[]
+    }
+}
+
+// what an action kind gets to look at when validating
+pub(crate) struct ActorView<'a> {
+    pub(crate) entity: Entity,
+    pub(crate) locus: &'a Locus,
+    pub(crate) stamina: &'a Stamina,
+    pub(crate) attributes: &'a Attributes,
+    pub(crate) mobility: Mobility,
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum ActionKindSet {
+    Validate,
+    Effect,
+    // readied actions, once effects have been seen
+    React,
+}
+
+type PhaseLookup = fn(&ActionDetail) -> Option<&'static [Phase]>;
+
+// everything registered, for the parts of the action system which handle any kind
+#[derive(Resource, Default)]
+pub(crate) struct ActionKinds {
+    phases: Vec<PhaseLookup>,
+}
+
+impl ActionKinds {
+    fn register<K: ActionKind>(&mut self) {
+        self.phases
+            .push(|detail| K::from_detail(detail).map(|k| k.phases()));
+    }
+
+    pub(crate) fn phases(&self, detail: &ActionDetail) -> &'static [Phase] {
+        self.phases
+            .iter()
+            .find_map(|lookup| lookup(detail))
+            .unwrap_or(&[])
+    }
+}
+
+pub(crate) trait RegisterActionKind {
+    fn register_action_kind<K: ActionKind>(&mut self) -> &mut Self;
+}
+
+impl RegisterActionKind for App {
+    fn register_action_kind<K: ActionKind>(&mut self) -> &mut Self {
+        self.init_resource::<ActionKinds>();
+        self.world.resource_mut::<ActionKinds>().register::<K>();
+        self.add_systems(
+            PreUpdate,
+            validate_kind::<K>.in_set(ActionKindSet::Validate),
+        )
+    }
+}
+
+// an action of this kind which has just finished, ready for its effects to be applied
+pub(crate) fn completed<K: ActionKind>(action: &ActorAction) -> Option<K> {
+    if action.0.is_complete() {
+        K::from_detail(&action.0.detail)
+    } else {
+        None
+    }
+}
+
+pub(crate) fn validate_kind<K: ActionKind>(
+    mut ev_invalid: EventWriter<ActionInvalidatedEvent>,
+    mut ev_valid: EventWriter<ActionValidatedEvent>,
+    mut query: Query<(Entity, &mut ActorAction, &Locus, &Stamina, MobilityQuery)>,
+    board: Res<Board>,
+) {
+    for (entity, mut action, locus, stamina, (pace, attributes, approach, conditions, stance)) in
+        query.iter_mut()
+    {
+        // queued actions are validated as they come up, so this runs every frame
+        if !action.0.is_idle() {
+            continue;
+        }
+        let Some(kind) = K::from_detail(&action.0.detail) else {
+            continue;
+        };
+
+        let actor = ActorView {
+            entity,
+            locus,
+            stamina,
+            attributes,
+            mobility: Mobility::new(pace, attributes, approach, conditions, stance),
+        };
+
+        if let Some(duration) = kind.duration(&actor, &board) {
+            action.0.duration = duration;
+        }
+
+        let valid = kind.validate(&actor, &board);
+        trace!("{:?} validity: {}", kind, valid);
+
+        if valid {
+            action.0.status = ActionStatus::Ready;
+            ev_valid.send(ActionValidatedEvent { entity });
+        } else {
+            action.0.status = ActionStatus::Aborted;
+            ev_invalid.send(ActionInvalidatedEvent {
+                entity,
+                detail: action.0.detail,
+            });
+        }
+    }
+}
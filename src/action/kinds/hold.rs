@@ -0,0 +1,502 @@
+use crate::action::duration::{Mobility, MobilityQuery};
+use crate::action::phase::{phase, Monitor::*, Phase};
+use crate::board::direction::DIRECTIONS;
+use crate::board::TerrainChangedEvent;
+use crate::creature::{initiative, is_hostile, Initiative, Stamina};
+
+use crate::action::command::Travel;
+
+use super::*;
+
+// Readied actions
+//
+// rather than act, a creature can hold its readiness: name what it's waiting
+// for, and what it'll do when it comes. Holding is an action like any other,
+// which lasts until it runs out or the trigger comes; readiness is lost to a
+// shock like any other phase.
+//
+// reactions are checked once everything completing in a tick has been applied,
+// and the response starts that same tick. When several go off at once:
+// - the quicker reflexes (then agility) go first;
+// - whoever is set off by someone who has just reacted against them is beaten
+//   to it, and loses their readiness;
+// - a reaction never sets off another in the same tick - it hasn't done
+//   anything yet.
+//
+// a target breaking cover has to have been out of sight first: anyone in
+// view when the hold is set, or still in view since, doesn't set it off.
+
+const HOLD_PHASES: [Phase; 1] = [phase("hold", 1, &[Conscious, Composed])];
+
+// how long readiness can be held before it has to be taken up again
+pub(crate) const HOLD_DURATION: Dur = Dur::seconds(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Trigger {
+    // an enemy steps within arm's reach
+    EnemyInReach,
+    // a door opens, or a wall comes down
+    TerrainChanged(IVec3),
+    // they break cover, into sight - having been out of it
+    TargetExposed(Entity),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Response {
+    // strike whoever set it off
+    Strike,
+    // loose an arrow at whoever set it off
+    Loose,
+    // step away - or into the gap
+    Step(Dir),
+}
+
+#[derive(Event, Debug, PartialEq, Eq, Clone, Copy, Component)]
+pub(crate) struct HoldDetail {
+    pub(crate) trigger: Trigger,
+    pub(crate) response: Response,
+}
+
+impl Action {
+    pub(crate) fn hold(entity: Entity, trigger: Trigger, response: Response) -> Self {
+        Action {
+            entity,
+            status: ActionStatus::Idle,
+            detail: ActionDetail::Hold(HoldDetail { trigger, response }),
+            duration: HOLD_DURATION,
+        }
+    }
+}
+
+impl Response {
+    fn action(&self, entity: Entity, source: Option<Entity>) -> Option<Action> {
+        match self {
+            Response::Strike => source.map(|target| Action::attack(entity, target)),
+            Response::Loose => source.map(|target| Action::shoot(entity, target)),
+            Response::Step(dir) => Some(Action::walk(entity, *dir)),
+        }
+    }
+}
+
+pub(crate) struct HoldPlugin;
+
+impl Plugin for HoldPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_action_kind::<HoldDetail>()
+            .add_event::<ReactionEvent>()
+            .add_systems(PreUpdate, react.in_set(ActionKindSet::React));
+    }
+}
+
+impl ActionKind for HoldDetail {
+    fn from_detail(detail: &ActionDetail) -> Option<Self> {
+        match detail {
+            ActionDetail::Hold(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    // the response is checked when it's set off; by then things will have changed
+    fn validate(&self, _actor: &ActorView, _board: &Board) -> bool {
+        true
+    }
+
+    fn phases(&self) -> &'static [Phase] {
+        &HOLD_PHASES
+    }
+}
+
+// a readied action has been set off
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ReactionEvent {
+    pub(crate) entity: Entity,
+    pub(crate) trigger: Trigger,
+    pub(crate) source: Option<Entity>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Reaction {
+    entity: Entity,
+    hold: HoldDetail,
+    source: Option<Entity>,
+    initiative: Initiative,
+}
+
+// which reactions go ahead, in order, and which are beaten to it
+fn order_reactions(mut reactions: Vec<Reaction>) -> (Vec<Reaction>, Vec<Reaction>) {
+    reactions.sort_by_key(|r| std::cmp::Reverse(r.initiative));
+
+    let mut fired: Vec<Reaction> = vec![];
+    let mut beaten: Vec<Reaction> = vec![];
+    for reaction in reactions {
+        let pre_empted = fired
+            .iter()
+            .any(|r| Some(r.entity) == reaction.source && r.source == Some(reaction.entity));
+        if pre_empted {
+            beaten.push(reaction);
+        } else {
+            fired.push(reaction);
+        }
+    }
+    (fired, beaten)
+}
+
+// whether the target of a TargetExposed hold was in sight when last looked
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Lookout {
+    start_tick: u32,
+    in_sight: bool,
+}
+
+fn target_in_sight(target: Entity, vision: &Vision, board: &Board) -> bool {
+    board
+        .creature_store
+        .pos_for(&target)
+        .is_some_and(|pos| vision.can_see(pos))
+}
+
+// note whether the target's in view as each hold is set
+pub(crate) fn arm_holds(
+    mut ev_start: EventReader<ActionStartedEvent>,
+    query: Query<(&ActorAction, &Vision)>,
+    mut commands: Commands,
+    board: Res<Board>,
+) {
+    for ev in ev_start.read() {
+        let Some(HoldDetail {
+            trigger: Trigger::TargetExposed(target),
+            ..
+        }) = HoldDetail::from_detail(&ev.detail)
+        else {
+            continue;
+        };
+        let Ok((action, vision)) = query.get(ev.entity) else {
+            continue;
+        };
+        let ActionStatus::Active { start_tick, .. } = action.0.status else {
+            continue;
+        };
+        commands.entity(ev.entity).insert(Lookout {
+            start_tick,
+            in_sight: target_in_sight(target, vision, &board),
+        });
+    }
+}
+
+// checks the response like any queued action, and works out how long it takes
+fn check<K: ActionKind>(action: &mut Action, actor: &ActorView, board: &Board) -> bool {
+    let Some(kind) = K::from_detail(&action.detail) else {
+        return false;
+    };
+    if let Some(duration) = kind.duration(actor, board) {
+        action.duration = duration;
+    }
+    kind.validate(actor, board)
+}
+
+pub(crate) fn react(
+    mut query: Query<(
+        Entity,
+        &mut ActorAction,
+        &mut Actor,
+        &Locus,
+        &Vision,
+        &Stamina,
+        MobilityQuery,
+        Option<&Player>,
+    )>,
+    players: Query<(), With<Player>>,
+    mut lookouts: Query<&mut Lookout>,
+    mut commands: Commands,
+    mut ev_terrain: EventReader<TerrainChangedEvent>,
+    mut ev_reaction: EventWriter<ReactionEvent>,
+    mut ev_start: EventWriter<ActionStartedEvent>,
+    mut ev_abort: EventWriter<ActionAbortedEvent>,
+    board: Res<Board>,
+    tick: Res<TickCount>,
+) {
+    let changed: HashSet<IVec3> = ev_terrain.read().map(|ev| ev.0).collect();
+    let mut sightings: Vec<(Entity, bool)> = vec![];
+
+    let reactions: Vec<Reaction> = query
+        .iter()
+        .filter_map(|(entity, action, _, locus, vision, _, mobility, _)| {
+            let ActionStatus::Active { start_tick, .. } = action.0.status else {
+                return None;
+            };
+            let hold = HoldDetail::from_detail(&action.0.detail)?;
+            // whether it's been set off, and by whom
+            let set_off: Option<Option<Entity>> = match hold.trigger {
+                Trigger::EnemyInReach => DIRECTIONS
+                    .iter()
+                    .filter_map(|dir| {
+                        board
+                            .creature_store
+                            .entity_at(&(locus.position + dir.offset()))
+                    })
+                    .copied()
+                    .find(|other| is_hostile(&players, entity, *other))
+                    .map(Some),
+                // a door opening with nobody in it sets things off too
+                Trigger::TerrainChanged(pos) => changed
+                    .contains(&pos)
+                    .then(|| board.creature_store.entity_at(&pos).copied()),
+                // with no note of how it was, assume they were already in view
+                Trigger::TargetExposed(target) => {
+                    let in_sight = target_in_sight(target, vision, &board);
+                    let hidden_before = lookouts
+                        .get(entity)
+                        .is_ok_and(|l| l.start_tick == start_tick && !l.in_sight);
+                    sightings.push((entity, in_sight));
+                    (in_sight && hidden_before).then_some(Some(target))
+                }
+            };
+            let source = set_off?;
+
+            let (_, attributes, _, _, _) = mobility;
+            Some(Reaction {
+                entity,
+                hold,
+                source,
+                initiative: initiative(entity, attributes),
+            })
+        })
+        .collect();
+
+    for (entity, in_sight) in sightings {
+        if let Ok(mut lookout) = lookouts.get_mut(entity) {
+            lookout.in_sight = in_sight;
+        }
+    }
+
+    let (fired, beaten) = order_reactions(reactions);
+
+    for reaction in fired.iter() {
+        let Ok((entity, mut action, mut actor, locus, _, stamina, mobility, player)) =
+            query.get_mut(reaction.entity)
+        else {
+            continue;
+        };
+        ev_reaction.send(ReactionEvent {
+            entity,
+            trigger: reaction.hold.trigger,
+            source: reaction.source,
+        });
+
+        let (pace, attributes, approach, conditions, stance) = mobility;
+        let view = ActorView {
+            entity,
+            locus,
+            stamina,
+            attributes,
+            mobility: Mobility::new(pace, attributes, approach, conditions, stance),
+        };
+        let mut response = reaction.hold.response.action(entity, reaction.source);
+        let valid = response.as_mut().is_some_and(|response| {
+            check::<MeleeCombatActionDetail>(response, &view, &board)
+                || check::<MissileCombatActionDetail>(response, &view, &board)
+                || check::<MovementActionDetail>(response, &view, &board)
+        });
+
+        match response {
+            Some(mut response) if valid => {
+                info!(
+                    "{:?} reacts to {:?}: {:?}",
+                    entity, reaction.hold.trigger, response.detail
+                );
+                response.start(tick.0);
+                action.0 = response;
+                ev_start.send(ActionStartedEvent {
+                    entity,
+                    detail: action.0.detail,
+                });
+            }
+            _ => {
+                info!("{:?} is set off, but can't respond", entity);
+                lose_readiness(&mut commands, entity, &mut actor, player.is_some());
+                ev_abort.send(ActionAbortedEvent {
+                    entity,
+                    detail: Some(ActionDetail::Hold(reaction.hold)),
+                });
+            }
+        }
+    }
+
+    for reaction in beaten.iter() {
+        let Ok((entity, _, mut actor, _, _, _, _, player)) = query.get_mut(reaction.entity) else {
+            continue;
+        };
+        info!("{:?} is beaten to it by {:?}", entity, reaction.source);
+        lose_readiness(&mut commands, entity, &mut actor, player.is_some());
+        ev_abort.send(ActionAbortedEvent {
+            entity,
+            detail: Some(ActionDetail::Hold(reaction.hold)),
+        });
+    }
+}
+
+fn lose_readiness(commands: &mut Commands, entity: Entity, actor: &mut Actor, is_player: bool) {
+    actor.clear_queue();
+    commands.entity(entity).remove::<(ActorAction, Travel)>();
+    if !is_player {
+        commands.entity(entity).insert(ActionPlanRequestMarker);
+    }
+}
+
+#[test]
+fn test_quicker_reaction_wins() {
+    let e = |id: u32| Entity::from_raw(id);
+    let reaction = |id: u32, source: u32, reflexes: u8| Reaction {
+        entity: e(id),
+        hold: HoldDetail {
+            trigger: Trigger::EnemyInReach,
+            response: Response::Strike,
+        },
+        source: Some(e(source)),
+        initiative: (reflexes, 5, std::cmp::Reverse(id)),
+    };
+
+    // two creatures step up to each other, both braced: the quicker strikes first
+    let (fired, beaten) = order_reactions(vec![reaction(1, 2, 4), reaction(2, 1, 7)]);
+    assert_eq!(fired.iter().map(|r| r.entity).collect::<Vec<_>>(), [e(2)]);
+    assert_eq!(beaten.iter().map(|r| r.entity).collect::<Vec<_>>(), [e(1)]);
+
+    // set off by someone else entirely: both go, quickest first
+    let (fired, beaten) = order_reactions(vec![reaction(1, 3, 4), reaction(2, 3, 7)]);
+    assert_eq!(
+        fired.iter().map(|r| r.entity).collect::<Vec<_>>(),
+        [e(2), e(1)]
+    );
+    assert!(beaten.is_empty());
+}
+
+#[test]
+fn test_step_into_the_gap() {
+    use crate::board::announce_terrain_changes;
+    use crate::creature::CreatureBundle;
+
+    let mut app = App::new();
+    app.init_resource::<Board>()
+        .init_resource::<TickCount>()
+        .add_event::<TerrainChangedEvent>()
+        .add_event::<ReactionEvent>()
+        .add_event::<ActionStartedEvent>()
+        .add_event::<ActionAbortedEvent>()
+        .add_systems(Update, (announce_terrain_changes, react).chain());
+
+    let position = IVec3::new(4, 4, 0);
+    let gap = position + Dir::East.offset();
+    app.world
+        .resource_mut::<Board>()
+        .set_wall(gap, Entity::from_raw(999));
+    let entity = app
+        .world
+        .spawn(CreatureBundle {
+            locus: Locus {
+                position,
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+    // the wall going up is over and done with before anyone's waiting on it
+    app.update();
+
+    let mut hold = Action::hold(
+        entity,
+        Trigger::TerrainChanged(gap),
+        Response::Step(Dir::East),
+    );
+    hold.start(0);
+    app.world.entity_mut(entity).insert(ActorAction(hold));
+    app.update();
+    assert_eq!(app.world.get::<ActorAction>(entity).unwrap().0, hold);
+
+    // the wall comes down, and in they go
+    app.world.resource_mut::<Board>().remove_wall(&gap);
+    app.update();
+    let action = app.world.get::<ActorAction>(entity).unwrap().0;
+    assert_eq!(
+        action.detail,
+        ActionDetail::Move(MovementActionDetail::Walk(Dir::East))
+    );
+    assert!(action.is_active());
+    let reactions = app.world.resource::<Events<ReactionEvent>>();
+    assert_eq!(
+        reactions.get_reader().read(reactions).next(),
+        Some(&ReactionEvent {
+            entity,
+            trigger: Trigger::TerrainChanged(gap),
+            source: None,
+        })
+    );
+}
+
+#[test]
+fn test_only_breaking_cover_counts() {
+    use crate::creature::CreatureBundle;
+
+    let mut app = App::new();
+    app.init_resource::<Board>()
+        .init_resource::<TickCount>()
+        .add_event::<TerrainChangedEvent>()
+        .add_event::<ReactionEvent>()
+        .add_event::<ActionStartedEvent>()
+        .add_event::<ActionAbortedEvent>()
+        .add_systems(Update, (arm_holds, apply_deferred, react).chain());
+
+    let spawn = |app: &mut App, position: IVec3| {
+        let entity = app
+            .world
+            .spawn(CreatureBundle {
+                locus: Locus {
+                    position,
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+        app.world
+            .resource_mut::<Board>()
+            .creature_store
+            .insert(entity, position);
+        entity
+    };
+    let archer = spawn(&mut app, IVec3::new(2, 2, 0));
+    let target_pos = IVec3::new(6, 2, 0);
+    let target = spawn(&mut app, target_pos);
+    let in_view = |app: &mut App, seen: bool| {
+        let mut vision = app.world.get_mut::<Vision>(archer).unwrap();
+        if seen {
+            vision.focus.insert(target_pos);
+        } else {
+            vision.focus.remove(&target_pos);
+        }
+    };
+
+    // set with the target already in plain view
+    in_view(&mut app, true);
+    let mut hold = Action::hold(archer, Trigger::TargetExposed(target), Response::Loose);
+    hold.start(0);
+    app.world.entity_mut(archer).insert(ActorAction(hold));
+    app.world.send_event(ActionStartedEvent {
+        entity: archer,
+        detail: hold.detail,
+    });
+
+    let mut reactions = app.world.resource::<Events<ReactionEvent>>().get_reader();
+    let mut fired = |app: &mut App| {
+        app.update();
+        reactions
+            .read(app.world.resource::<Events<ReactionEvent>>())
+            .count()
+    };
+    assert_eq!(fired(&mut app), 0);
+    assert_eq!(fired(&mut app), 0);
+
+    // they duck out of sight, then show themselves again
+    in_view(&mut app, false);
+    assert_eq!(fired(&mut app), 0);
+    in_view(&mut app, true);
+    assert_eq!(fired(&mut app), 1);
+}
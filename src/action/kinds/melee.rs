@@ -0,0 +1,108 @@
+use crate::action::momentum::closing_speed;
+use crate::action::phase::{phase, Monitor::*, Phase};
+use crate::creature::check::{Attribute, Check};
+use crate::creature::Attributes;
+
+use super::*;
+
+const MELEE_PHASES: [Phase; 2] = [
+    phase("wind up", 4, &[Conscious, TargetInReach]),
+    phase("strike", 6, &[Conscious, TargetInReach]),
+];
+
+// impact worth calling a charge: a running start at someone
+pub(crate) const CHARGE_IMPACT: u32 = 20;
+
+pub(crate) struct MeleePlugin;
+
+impl Plugin for MeleePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_action_kind::<MeleeCombatActionDetail>()
+            .add_event::<AttackEvent>()
+            .add_systems(PreUpdate, apply_attack.in_set(ActionKindSet::Effect));
+    }
+}
+
+impl ActionKind for MeleeCombatActionDetail {
+    fn from_detail(detail: &ActionDetail) -> Option<Self> {
+        match detail {
+            ActionDetail::Attack(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    // TODO reach weapons, and whether the target can be seen
+    fn validate(&self, actor: &ActorView, board: &Board) -> bool {
+        self.target()
+            .and_then(|target| board.creature_store.pos_for(&target))
+            .is_some_and(|pos| {
+                let d = (*pos - actor.locus.position).abs();
+                d.x.max(d.y) == 1 && d.z == 0
+            })
+    }
+
+    fn duration(&self, _actor: &ActorView, _board: &Board) -> Option<Dur> {
+        Some(Dur::ticks(MELEE_PHASES.iter().map(|p| p.ticks).sum()))
+    }
+
+    fn phases(&self) -> &'static [Phase] {
+        &MELEE_PHASES
+    }
+}
+
+// a blow has landed - or at least been thrown
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct AttackEvent {
+    pub(crate) attacker: Entity,
+    pub(crate) target: Entity,
+    // weight behind it, from a run up: kg cells per tick
+    pub(crate) impact: u32,
+    pub(crate) hit: bool,
+}
+
+// a blow at someone standing like that: harder if they're on guard, easier
+// if they're down
+pub(crate) fn blow(target_stance: Stance) -> Check {
+    Check::new(Attribute::Dexterity, Attribute::Agility, |s| s.melee)
+        .with_advantages(-target_stance.melee_defence())
+}
+
+pub(crate) fn apply_attack(
+    mut query: Query<(Entity, &ActorAction, &mut Locus, &Attributes, &SkillList)>,
+    stances: Query<&Stance>,
+    mut ev_attack: EventWriter<AttackEvent>,
+    board: Res<Board>,
+    mut global_rng: ResMut<GlobalChaChaRng>,
+) {
+    let mut rng = RngComponent::from(&mut global_rng);
+
+    for (entity, action, mut locus, attributes, skills) in query.iter_mut() {
+        let Some(attack) = completed::<MeleeCombatActionDetail>(action) else {
+            continue;
+        };
+        if let Some(target) = attack.target() {
+            let mut impact = 0;
+            // turn to face them
+            if let Some(pos) = board.creature_store.pos_for(&target) {
+                if let Some(dir) = Dir::from_offset(*pos - locus.position) {
+                    locus.facing = dir;
+                }
+                impact = (closing_speed(&locus, *pos) * locus.weight as f32).round() as u32;
+            }
+            // whatever momentum there was has gone into it
+            locus.velocity = Vec3::ZERO;
+            let stance = stances.get(target).copied().unwrap_or_default();
+            let result = blow(stance).roll(attributes, skills, &mut rng);
+            info!(
+                "{:?} attacks {:?} (impact {}, hit: {})",
+                entity, target, impact, result.success
+            );
+            ev_attack.send(AttackEvent {
+                attacker: entity,
+                target,
+                impact,
+                hit: result.success,
+            });
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::action::phase::{phase, Monitor::*, Phase};
+use crate::creature::check::{Attribute, Check};
+use crate::creature::{cover, Attributes, Cover};
+
+use super::*;
+
+const BOW_PHASES: [Phase; 5] = [
+    phase("draw", 6, &[Conscious, Composed]),
+    phase("nock", 4, &[Conscious, Composed]),
+    phase("pull", 5, &[Conscious, Composed]),
+    phase("aim", 10, &[Conscious, Composed, TargetInSight]),
+    phase("release", 1, &[Conscious, TargetInSight]),
+];
+
+// a low wall between them is worth this much on top of their stance
+const PARTIAL_COVER_DEFENCE: i32 = 4;
+
+pub(crate) struct MissilePlugin;
+
+impl Plugin for MissilePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_action_kind::<MissileCombatActionDetail>()
+            .add_systems(PreUpdate, apply_shot.in_set(ActionKindSet::Effect));
+    }
+}
+
+impl ActionKind for MissileCombatActionDetail {
+    fn from_detail(detail: &ActionDetail) -> Option<Self> {
+        match detail {
+            ActionDetail::Shoot(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    // TODO range, ammunition, and a bow in hand
+    fn validate(&self, actor: &ActorView, board: &Board) -> bool {
+        self.target()
+            .and_then(|target| board.creature_store.pos_for(&target))
+            .is_some_and(|pos| board.has_line_of_sight(actor.locus.position, *pos))
+    }
+
+    fn duration(&self, _actor: &ActorView, _board: &Board) -> Option<Dur> {
+        Some(Dur::ticks(BOW_PHASES.iter().map(|p| p.ticks).sum()))
+    }
+
+    fn phases(&self) -> &'static [Phase] {
+        &BOW_PHASES
+    }
+}
+
+// a shot at someone standing like that, from behind whatever they have; none
+// at all if they're out of sight behind it
+pub(crate) fn shot(target_stance: Stance, cover: Cover) -> Option<Check> {
+    let cover_defence = match cover {
+        Cover::Exposed => 0,
+        Cover::Partial => PARTIAL_COVER_DEFENCE,
+        Cover::Full => return None,
+    };
+    let check = Check::new(Attribute::Dexterity, Attribute::Perception, |s| s.bow);
+    Some(check.with_advantages(-target_stance.missile_defence() - cover_defence))
+}
+
+pub(crate) fn apply_shot(
+    query: Query<(Entity, &ActorAction, &Locus, &Attributes, &SkillList)>,
+    stances: Query<&Stance>,
+    board: Res<Board>,
+    mut global_rng: ResMut<GlobalChaChaRng>,
+) {
+    let mut rng = RngComponent::from(&mut global_rng);
+
+    for (entity, action, locus, attributes, skills) in query.iter() {
+        let Some(target) = completed::<MissileCombatActionDetail>(action).and_then(|s| s.target())
+        else {
+            continue;
+        };
+        let Some(pos) = board.creature_store.pos_for(&target) else {
+            continue;
+        };
+        let stance = stances.get(target).copied().unwrap_or_default();
+        let hit = shot(stance, cover(&board, *pos, locus.position, stance))
+            .is_some_and(|check| check.roll(attributes, skills, &mut rng).success);
+        info!(
+            "{:?} looses an arrow at {:?} (hit: {})",
+            entity, target, hit
+        );
+    }
+}
+
+#[test]
+fn test_harder_to_hit_down_and_behind_something() {
+    let attributes = Attributes::uniform(5);
+    let skills = SkillList::default();
+    let chance = |stance: Stance, cover: Cover| {
+        shot(stance, cover).map_or(0.0, |c| c.probability(&attributes, &skills))
+    };
+
+    assert!(chance(Stance::Prone, Cover::Exposed) < chance(Stance::Standing, Cover::Exposed));
+    assert!(chance(Stance::Standing, Cover::Partial) < chance(Stance::Standing, Cover::Exposed));
+    assert_eq!(chance(Stance::Kneeling, Cover::Full), 0.0);
+
+    // up close, it's the other way about
+    let blow = |stance| super::melee::blow(stance).probability(&attributes, &skills);
+    assert!(blow(Stance::Prone) > blow(Stance::Standing));
+    assert!(blow(Stance::Dynamic) < blow(Stance::Standing));
+}
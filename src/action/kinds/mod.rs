@@ -0,0 +1,26 @@
+use super::kind::*;
+use super::*;
+
+// one module per kind of action, each with its own plugin
+
+pub(crate) mod hold;
+pub(crate) mod melee;
+pub(crate) mod missile;
+pub(crate) mod movement;
+pub(crate) mod stance;
+pub(crate) mod wait;
+
+pub(crate) struct ActionKindsPlugin;
+
+impl Plugin for ActionKindsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            movement::MovementPlugin,
+            melee::MeleePlugin,
+            missile::MissilePlugin,
+            hold::HoldPlugin,
+            wait::WaitPlugin,
+            stance::StancePlugin,
+        ));
+    }
+}
@@ -0,0 +1,377 @@
+use crate::action::duration::{Mobility, MobilityQuery};
+use crate::action::momentum;
+use crate::creature::{initiative, Attributes, Initiative, Stamina};
+use crate::graphics::anim::LerpVec3;
+
+use crate::action::command::Travel;
+
+use super::*;
+
+pub(crate) struct MovementPlugin;
+
+impl Plugin for MovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_action_kind::<MovementActionDetail>()
+            .add_event::<BumpEvent>()
+            .add_systems(
+                PreUpdate,
+                (resolve_move_conflicts, apply_deferred, apply_move)
+                    .chain()
+                    .in_set(ActionKindSet::Effect),
+            )
+            .add_systems(PreUpdate, come_to_rest.in_set(ActionKindSet::Effect));
+    }
+}
+
+impl ActionKind for MovementActionDetail {
+    fn from_detail(detail: &ActionDetail) -> Option<Self> {
+        match detail {
+            ActionDetail::Move(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    // TODO check for issues other than collisions with walls & creatures
+    fn validate(&self, actor: &ActorView, board: &Board) -> bool {
+        let can_enter = |dir: &Dir| {
+            board
+                .apply_direction(&actor.locus.position, dir)
+                .is_ok_and(|dest| {
+                    board.is_unoccupied(&dest) && board.creature_store.entity_at(&dest).is_none()
+                })
+        };
+
+        match self {
+            MovementActionDetail::Turn(_) => true,
+            MovementActionDetail::Walk(dir) => can_enter(dir),
+            MovementActionDetail::Run(dir) => {
+                actor.stamina.can_spend(RUN_STAMINA_COST)
+                    && actor.mobility.stance().can_run()
+                    && can_enter(dir)
+            }
+        }
+    }
+
+    fn duration(&self, actor: &ActorView, board: &Board) -> Option<Dur> {
+        Some(actor.mobility.stride_duration(self, board, actor.locus))
+    }
+}
+
+// Conflicts
+//
+// moves complete together, so two creatures may both have set off for the same
+// empty cell, or tried to walk through each other. The quicker one gets there;
+// the other bumps into them and stops. The way can also shut while a step is
+// under way - a door swinging closed - and then it's the door they bump into.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Bump {
+    // someone else got there first
+    Contested,
+    // two creatures trying to step past one another
+    Swap,
+    // someone's standing there, and isn't going anywhere
+    Occupied,
+    // there's no getting in: a wall, a shut door, the edge of the board
+    Blocked,
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BumpEvent {
+    pub(crate) entity: Entity,
+    pub(crate) into: Option<Entity>,
+    pub(crate) at: IVec3,
+    pub(crate) bump: Bump,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Step {
+    entity: Entity,
+    from: IVec3,
+    to: IVec3,
+    initiative: Initiative,
+}
+
+// who doesn't get where they were going, and why
+fn contest(
+    steps: &[Step],
+    passable: impl Fn(&IVec3) -> bool,
+    occupant: impl Fn(&IVec3) -> Option<Entity>,
+) -> Vec<BumpEvent> {
+    let mut bumps: Vec<BumpEvent> = vec![];
+    let bumped = |bumps: &[BumpEvent], entity: Entity| bumps.iter().any(|b| b.entity == entity);
+    let bump = |step: &Step, into: Option<Entity>, bump: Bump| BumpEvent {
+        entity: step.entity,
+        into,
+        at: step.to,
+        bump,
+    };
+
+    for step in steps.iter().filter(|s| !passable(&s.to)) {
+        bumps.push(bump(step, None, Bump::Blocked));
+    }
+
+    for a in steps.iter().filter(|s| !bumped(&bumps, s.entity)) {
+        if let Some(b) = steps.iter().find(|b| b.from == a.to && b.to == a.from) {
+            bumps.push(bump(a, Some(b.entity), Bump::Swap));
+        }
+    }
+
+    let mut by_destination: HashMap<IVec3, Vec<&Step>> = HashMap::new();
+    for step in steps.iter().filter(|s| !bumped(&bumps, s.entity)) {
+        by_destination.entry(step.to).or_default().push(step);
+    }
+    for contenders in by_destination.values_mut() {
+        contenders.sort_by_key(|s| std::cmp::Reverse(s.initiative));
+        let winner = contenders[0].entity;
+        for loser in &contenders[1..] {
+            bumps.push(bump(loser, Some(winner), Bump::Contested));
+        }
+    }
+
+    // anyone stepping into a cell which isn't being vacated - which can
+    // change as others are bumped, so repeat until nothing does
+    loop {
+        let blocked = steps.iter().find(|step| {
+            if bumped(&bumps, step.entity) {
+                return false;
+            }
+            occupant(&step.to).is_some_and(|occupant| {
+                occupant != step.entity
+                    && !steps
+                        .iter()
+                        .any(|s| s.entity == occupant && !bumped(&bumps, occupant))
+            })
+        });
+        match blocked {
+            Some(step) => {
+                let b = bump(step, occupant(&step.to), Bump::Occupied);
+                bumps.push(b);
+            }
+            None => break,
+        }
+    }
+
+    bumps
+}
+
+pub(crate) fn resolve_move_conflicts(
+    mut query: Query<(
+        Entity,
+        &mut ActorAction,
+        &mut Actor,
+        &mut Locus,
+        &Attributes,
+        Option<&Player>,
+    )>,
+    mut commands: Commands,
+    mut ev_bump: EventWriter<BumpEvent>,
+    mut ev_abort: EventWriter<ActionAbortedEvent>,
+    board: Res<Board>,
+) {
+    let steps: Vec<Step> = query
+        .iter()
+        .filter_map(|(entity, action, _, locus, attributes, _)| {
+            let mov = completed::<MovementActionDetail>(action)?;
+            if let MovementActionDetail::Turn(_) = mov {
+                return None;
+            }
+            Some(Step {
+                entity,
+                from: locus.position,
+                to: locus.position + mov.direction().offset(),
+                initiative: initiative(entity, attributes),
+            })
+        })
+        .collect();
+
+    let passable = |pos: &IVec3| board.terrain_cost(pos).is_some();
+    let occupant = |pos: &IVec3| board.creature_store.entity_at(pos).copied();
+    for bump in contest(&steps, passable, occupant) {
+        info!(
+            "{:?} bumps into {:?} at {:?} ({:?})",
+            bump.entity, bump.into, bump.at, bump.bump
+        );
+        ev_bump.send(bump);
+
+        let Ok((entity, mut action, mut actor, mut locus, _, player)) = query.get_mut(bump.entity)
+        else {
+            continue;
+        };
+        // the step is lost, along with anything queued after it - and any speed
+        locus.velocity = Vec3::ZERO;
+        action.0.status = ActionStatus::Aborted;
+        actor.clear_queue();
+        commands.entity(entity).remove::<(ActorAction, Travel)>();
+        if player.is_none() {
+            commands.entity(entity).insert(ActionPlanRequestMarker);
+        }
+        ev_abort.send(ActionAbortedEvent {
+            entity,
+            detail: Some(action.0.detail),
+        });
+    }
+}
+
+pub(crate) fn apply_move(
+    mut query_logic: Query<(
+        Entity,
+        &ActorAction,
+        &mut Locus,
+        &mut Stamina,
+        MobilityQuery,
+    )>,
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    mut global_rng: ResMut<GlobalChaChaRng>,
+) {
+    let mut rng = RngComponent::from(&mut global_rng);
+
+    for (entity, action, mut locus, mut stamina, mobility) in query_logic.iter_mut() {
+        let Some(mov) = completed::<MovementActionDetail>(action) else {
+            continue;
+        };
+        let pos = locus.position;
+
+        // turning on the spot: nothing to animate
+        if let MovementActionDetail::Turn(dir) = mov {
+            locus.facing = dir;
+            locus.velocity = Vec3::ZERO;
+            stamina.recover(STAMINA_RECOVERY);
+            continue;
+        }
+
+        // update the logical model
+        let dir = *mov.direction();
+        // anything blocked was bumped in resolve_move_conflicts
+        let Ok(dest) = board.apply_direction(&pos, &dir) else {
+            continue;
+        };
+
+        if let MovementActionDetail::Run(_) = mov {
+            stamina.spend(RUN_STAMINA_COST);
+        } else {
+            stamina.recover(STAMINA_RECOVERY);
+        }
+
+        // cutting a corner at speed, over rough ground
+        let (pace, attributes, approach, conditions, stance) = mobility;
+        let mobility = Mobility::new(pace, attributes, approach, conditions, stance);
+        let top_ticks = mobility.full_speed_ticks(&mov).unwrap_or(1.0);
+        let rough = board.terrain_cost(&dest).is_some_and(|cost| cost > 1);
+        let stumbled = rough
+            && momentum::is_sharp_turn(&locus, dir, top_ticks)
+            && momentum::stumbles(
+                (locus.velocity.length() * top_ticks).min(1.0),
+                attributes.primary.agility,
+                rng.i32(1..=10),
+            );
+
+        locus.facing = dir;
+        locus.direction = dir;
+        locus.position = dest;
+        // cells per tick, as fast as the step actually went
+        locus.velocity = (dest - pos).as_vec3() / action.0.duration.as_u32().max(1) as f32;
+
+        if stumbled {
+            info!("{:?} stumbles at {:?}", entity, dest);
+            locus.velocity = Vec3::ZERO;
+            commands.entity(entity).insert(Stance::Prone);
+        }
+
+        board.creature_store.update(entity, locus.position);
+
+        // add an animation marker to the graphics
+        let anim = LerpVec3::from_translation(pos.as_vec3(), dest.as_vec3(), 6);
+        commands.entity(entity).insert(anim);
+    }
+}
+
+// anything but moving - or striking with what momentum's left - brings a creature to rest
+pub(crate) fn come_to_rest(mut query: Query<(&ActorAction, &mut Locus)>) {
+    for (action, mut locus) in query.iter_mut() {
+        if !action.0.is_complete() || locus.velocity == Vec3::ZERO {
+            continue;
+        }
+        if !matches!(
+            action.0.detail,
+            ActionDetail::Move(_) | ActionDetail::Attack(_)
+        ) {
+            locus.velocity = Vec3::ZERO;
+        }
+    }
+}
+
+#[test]
+fn test_contested_and_swapped_moves() {
+    let step = |id: u32, from: [i32; 2], to: [i32; 2], reflexes: u8| Step {
+        entity: Entity::from_raw(id),
+        from: IVec3::new(from[0], from[1], 0),
+        to: IVec3::new(to[0], to[1], 0),
+        initiative: (reflexes, 5, std::cmp::Reverse(id)),
+    };
+
+    // two heading for the same cell: the quicker gets it
+    let steps = [step(1, [0, 0], [1, 1], 3), step(2, [2, 2], [1, 1], 7)];
+    let bumps = contest(&steps, |_| true, |_| None);
+    assert_eq!(bumps.len(), 1);
+    assert_eq!(bumps[0].entity, Entity::from_raw(1));
+    assert_eq!(bumps[0].bump, Bump::Contested);
+
+    // walking through each other: neither does
+    let steps = [step(1, [0, 0], [1, 0], 3), step(2, [1, 0], [0, 0], 7)];
+    let occupant = |pos: &IVec3| match pos.x {
+        0 => Some(Entity::from_raw(1)),
+        _ => Some(Entity::from_raw(2)),
+    };
+    let bumps = contest(&steps, |_| true, occupant);
+    assert_eq!(bumps.len(), 2);
+    assert!(bumps.iter().all(|b| b.bump == Bump::Swap));
+
+    // following someone who's stopped short
+    let steps = [step(1, [0, 0], [1, 0], 3), step(2, [1, 0], [2, 0], 3)];
+    let occupant = |pos: &IVec3| match pos.x {
+        0 => Some(Entity::from_raw(1)),
+        1 => Some(Entity::from_raw(2)),
+        _ => Some(Entity::from_raw(3)),
+    };
+    let bumps = contest(&steps, |_| true, occupant);
+    assert_eq!(bumps.len(), 2);
+    assert_eq!(bumps[0].entity, Entity::from_raw(2));
+    assert_eq!(bumps[1].entity, Entity::from_raw(1));
+}
+
+#[test]
+fn test_door_shuts_mid_step() {
+    use crate::action::command::Command;
+    use crate::sim::Sim;
+
+    let mut sim = Sim::default();
+    sim.record::<BumpEvent>();
+    let start = IVec3::new(3, 3, 0);
+    let player = sim.spawn_player(start);
+
+    sim.command(player, Command::Walk(Dir::East), 1);
+    assert!(sim.run_until(|world| world
+        .get::<ActorAction>(player)
+        .is_some_and(|a| a.0.is_active())));
+
+    // and swings shut in their face
+    let door = start + Dir::East.offset();
+    let entity = sim.app.world.spawn_empty().id();
+    sim.app.world.resource_mut::<Board>().set_wall(door, entity);
+    assert!(sim.run_until(|world| Sim::is_idle(world, player)));
+
+    assert_eq!(sim.position(player), start);
+    assert_eq!(
+        sim.app
+            .world
+            .resource::<Board>()
+            .creature_store
+            .pos_for(&player),
+        Some(&start)
+    );
+    let bumps = sim.recorded::<BumpEvent>();
+    assert_eq!(bumps.len(), 1);
+    assert_eq!((bumps[0].at, bumps[0].bump), (door, Bump::Blocked));
+}
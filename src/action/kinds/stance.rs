@@ -0,0 +1,79 @@
+use super::*;
+
+// Changing stance
+//
+// dropping to a crouch, a knee or flat on the ground, getting back up, or
+// settling into a fighting stance. How long it takes depends on how far it is
+// and how quick the creature is; nothing else changes until it's done.
+
+impl Action {
+    pub(crate) fn change_stance(entity: Entity, stance: Stance) -> Self {
+        Action {
+            entity,
+            status: ActionStatus::Idle,
+            detail: ActionDetail::Stance(stance),
+            duration: Dur::ticks(1),
+        }
+    }
+}
+
+pub(crate) struct StancePlugin;
+
+impl Plugin for StancePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_action_kind::<Stance>()
+            .add_systems(PreUpdate, apply_stance.in_set(ActionKindSet::Effect));
+    }
+}
+
+impl ActionKind for Stance {
+    fn from_detail(detail: &ActionDetail) -> Option<Self> {
+        match detail {
+            ActionDetail::Stance(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    fn validate(&self, actor: &ActorView, _board: &Board) -> bool {
+        actor.mobility.stance() != *self
+    }
+
+    fn duration(&self, actor: &ActorView, _board: &Board) -> Option<Dur> {
+        Some(actor.mobility.stance_duration(*self))
+    }
+}
+
+pub(crate) fn apply_stance(mut query: Query<(Entity, &ActorAction, &mut Stance)>) {
+    for (entity, action, mut stance) in query.iter_mut() {
+        let Some(to) = completed::<Stance>(action) else {
+            continue;
+        };
+        info!("{:?} goes from {:?} to {:?}", entity, *stance, to);
+        *stance = to;
+    }
+}
+
+#[test]
+fn test_quick_creatures_hit_the_ground_sooner() {
+    use crate::action::duration::Mobility;
+    use crate::creature::{Approach, Attributes, ConditionList};
+
+    let mut attributes = Attributes::new();
+    let (pace, approach, conditions) = (
+        Pace::default(),
+        Approach::default(),
+        ConditionList::default(),
+    );
+    let mobility = |attributes: &Attributes, stance: Stance| {
+        Mobility::new(&pace, attributes, &approach, &conditions, &stance)
+    };
+
+    attributes.primary.speed = 5;
+    let average = mobility(&attributes, Stance::Standing).stance_duration(Stance::Prone);
+    attributes.primary.speed = 10;
+    let quick = mobility(&attributes, Stance::Standing).stance_duration(Stance::Prone);
+    let getting_up = mobility(&attributes, Stance::Prone).stance_duration(Stance::Standing);
+
+    assert!(quick < average);
+    assert!(getting_up > quick);
+}
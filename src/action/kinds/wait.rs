@@ -0,0 +1,324 @@
+use crate::action::command::Travel;
+use crate::action::kinds::melee::AttackEvent;
+use crate::action::kinds::movement::BumpEvent;
+use crate::creature::{
+    is_hostile, visible_creatures, Attributes, Condition, ConditionList, NeedList, Stamina,
+};
+
+use super::*;
+
+// Waiting
+//
+// standing about for a while, resting until recovered, or keeping still until
+// something happens. Any of them is cut short by a hostile coming into view,
+// a noise within earshot or a change in how the waiter is feeling - which is
+// the whole point of waiting until something happens, so that one finishes
+// rather than being called off.
+//
+// time spent waiting is time spent recovering: stamina comes back (twice as
+// fast resting), needs creep up, and a full rest shakes off exhaustion.
+// Wounds don't heal yet - nothing keeps track of them to heal.
+//
+// disturbances are looked for every tick anyone is waiting, not just when
+// something completes: a wait can be cut short by what happened last tick.
+
+// longest anyone waits without reconsidering
+pub(crate) const MAX_WAIT: Dur = Dur::minutes(1);
+// stamina per tick, before the recovery attribute
+const WAIT_RECOVERY: f64 = 1.0;
+const REST_RECOVERY: f64 = 2.0;
+// cells away a noise can be heard, before listening skill
+const HEARING_RANGE: i32 = 8;
+
+const SHAKEN_OFF_BY_REST: [Condition; 3] =
+    [Condition::Exhausted, Condition::Shocked, Condition::Dizzy];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Until {
+    // the time is up
+    Elapsed,
+    // stamina is back
+    Rested,
+    // anything happens
+    Disturbed,
+}
+
+impl Action {
+    pub(crate) fn wait_until(entity: Entity, until: Until) -> Self {
+        Action {
+            entity,
+            status: ActionStatus::Idle,
+            detail: ActionDetail::Wait(until),
+            duration: MAX_WAIT,
+        }
+    }
+}
+
+pub(crate) struct WaitPlugin;
+
+impl Plugin for WaitPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_action_kind::<Until>()
+            .add_systems(PreUpdate, apply_wait.in_set(ActionKindSet::Effect));
+    }
+}
+
+impl ActionKind for Until {
+    fn from_detail(detail: &ActionDetail) -> Option<Self> {
+        match detail {
+            ActionDetail::Wait(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    fn validate(&self, _actor: &ActorView, _board: &Board) -> bool {
+        true
+    }
+
+    // a rest lasts as long as it takes to get back to full strength
+    fn duration(&self, actor: &ActorView, _board: &Board) -> Option<Dur> {
+        match self {
+            Until::Rested => {
+                let missing = actor.stamina.max.saturating_sub(actor.stamina.current);
+                let rate = recovery_rate(Until::Rested, actor.attributes);
+                let ticks = (missing as f64 / rate).ceil() as u32;
+                Some(Dur::ticks(ticks).clamp(Dur::ticks(1), MAX_WAIT))
+            }
+            _ => None,
+        }
+    }
+}
+
+// stamina per tick
+fn recovery_rate(until: Until, attributes: &Attributes) -> f64 {
+    let base = match until {
+        Until::Rested => REST_RECOVERY,
+        _ => WAIT_RECOVERY,
+    };
+    base * attributes.secondary.recovery
+}
+
+fn recovered(until: Until, elapsed: u32, attributes: &Attributes) -> u32 {
+    (elapsed as f64 * recovery_rate(until, attributes)).round() as u32
+}
+
+// what a stretch of waiting does for the waiter, however it ended
+fn pass_time(
+    until: Until,
+    (start_tick, end_tick): (u32, u32),
+    attributes: &Attributes,
+    stamina: &mut Stamina,
+    needs: &mut NeedList,
+) {
+    let elapsed = end_tick.saturating_sub(start_tick);
+    stamina.recover(recovered(until, elapsed, attributes));
+    needs.pass_time(start_tick, end_tick, until == Until::Rested);
+}
+
+pub(crate) fn apply_wait(
+    mut query: Query<(
+        &ActorAction,
+        &Attributes,
+        &mut Stamina,
+        &mut NeedList,
+        &mut ConditionList,
+    )>,
+    tick: Res<TickCount>,
+) {
+    for (action, attributes, mut stamina, mut needs, mut conditions) in query.iter_mut() {
+        let Some(until) = completed::<Until>(action) else {
+            continue;
+        };
+        let start_tick = tick.0.saturating_sub(action.0.duration.as_u32());
+        pass_time(
+            until,
+            (start_tick, tick.0),
+            attributes,
+            &mut stamina,
+            &mut needs,
+        );
+
+        if until == Until::Rested {
+            for condition in SHAKEN_OFF_BY_REST {
+                conditions.remove(condition);
+            }
+        }
+    }
+}
+
+// what each waiter knew of when they settled down
+#[derive(Component, Debug, Clone)]
+pub(crate) struct Watch {
+    start_tick: u32,
+    seen: HashSet<Entity>,
+    conditions: ConditionList,
+}
+
+// take note of the surroundings as each wait begins
+pub(crate) fn watch_waits(
+    mut ev_start: EventReader<ActionStartedEvent>,
+    query: Query<(&ActorAction, &Vision, &ConditionList)>,
+    mut commands: Commands,
+    board: Res<Board>,
+) {
+    for ev in ev_start.read() {
+        if Until::from_detail(&ev.detail).is_none() {
+            continue;
+        }
+        let Ok((action, vision, conditions)) = query.get(ev.entity) else {
+            continue;
+        };
+        let ActionStatus::Active { start_tick, .. } = action.0.status else {
+            continue;
+        };
+        commands.entity(ev.entity).insert(Watch {
+            start_tick,
+            seen: visible_creatures(vision, &board, ev.entity),
+            conditions: conditions.clone(),
+        });
+    }
+}
+
+pub(crate) fn disturb_waits(
+    mut query: Query<(
+        Entity,
+        &mut ActorAction,
+        &mut Actor,
+        &Watch,
+        &Locus,
+        &Vision,
+        &SkillList,
+        (&Attributes, &mut Stamina, &mut NeedList, &ConditionList),
+        Option<&Player>,
+    )>,
+    players: Query<(), With<Player>>,
+    mut commands: Commands,
+    mut ev_attack: EventReader<AttackEvent>,
+    mut ev_bump: EventReader<BumpEvent>,
+    mut ev_abort: EventWriter<ActionAbortedEvent>,
+    board: Res<Board>,
+    tick: Res<TickCount>,
+) {
+    // who made a noise, and where
+    let noises: Vec<(Entity, IVec3)> = ev_attack
+        .read()
+        .filter_map(|ev| {
+            let pos = board.creature_store.pos_for(&ev.attacker)?;
+            Some((ev.attacker, *pos))
+        })
+        .chain(ev_bump.read().map(|ev| (ev.entity, ev.at)))
+        .collect();
+
+    for (entity, mut action, mut actor, watch, locus, vision, skills, recovery, player) in
+        query.iter_mut()
+    {
+        let ActionStatus::Active {
+            start_tick,
+            complete_tick,
+        } = action.0.status
+        else {
+            continue;
+        };
+        let Some(until) = Until::from_detail(&action.0.detail) else {
+            continue;
+        };
+        if watch.start_tick != start_tick {
+            continue;
+        }
+
+        let spotted = visible_creatures(vision, &board, entity)
+            .difference(&watch.seen)
+            .any(|other| is_hostile(&players, entity, *other));
+        let heard = noises.iter().any(|(source, pos)| {
+            let d = (*pos - locus.position).abs();
+            *source != entity && d.x.max(d.y) <= HEARING_RANGE + skills.listen as i32
+        });
+        let (attributes, mut stamina, mut needs, conditions) = recovery;
+        let unsettled = *conditions != watch.conditions;
+
+        if !(spotted || heard || unsettled) {
+            continue;
+        }
+        info!(
+            "{:?} disturbed while waiting (spotted: {}, heard: {}, unsettled: {})",
+            entity, spotted, heard, unsettled
+        );
+
+        if until == Until::Disturbed {
+            // what they were waiting for: done with the next tick
+            let complete_tick = complete_tick.min(tick.0 + 1);
+            action.0.status = ActionStatus::Active {
+                start_tick,
+                complete_tick,
+            };
+            action.0.duration = Dur::between(start_tick, complete_tick);
+            continue;
+        }
+
+        // cut short, but they got some rest in
+        pass_time(
+            until,
+            (start_tick, tick.0),
+            attributes,
+            &mut stamina,
+            &mut needs,
+        );
+        action.0.status = ActionStatus::Aborted;
+        actor.clear_queue();
+        commands
+            .entity(entity)
+            .remove::<(ActorAction, Travel, Watch)>();
+        if player.is_none() {
+            commands.entity(entity).insert(ActionPlanRequestMarker);
+        }
+        ev_abort.send(ActionAbortedEvent {
+            entity,
+            detail: Some(action.0.detail),
+        });
+    }
+}
+
+#[test]
+fn test_resting_recovers_faster() {
+    use crate::action::duration::Mobility;
+    use crate::creature::Approach;
+
+    let attributes = Attributes::uniform(5);
+    assert_eq!(recovered(Until::Elapsed, 10, &attributes), 10);
+    assert_eq!(recovered(Until::Rested, 10, &attributes), 20);
+
+    let mut stamina = Stamina::new(&attributes.secondary);
+    let mut needs = NeedList::default();
+    stamina.spend(30);
+    pass_time(
+        Until::Rested,
+        (0, 10),
+        &attributes,
+        &mut stamina,
+        &mut needs,
+    );
+    assert_eq!(stamina.current, stamina.max - 10);
+
+    // a rest is long enough to get it all back, however quickly someone recovers
+    let mut attributes = attributes;
+    attributes.secondary.recovery = 2.0;
+    let duration = |stamina: &Stamina| {
+        let mobility = Mobility::new(
+            &Pace::default(),
+            &attributes,
+            &Approach::default(),
+            &ConditionList::default(),
+            &Stance::default(),
+        );
+        let view = ActorView {
+            entity: Entity::from_raw(1),
+            locus: &Locus::default(),
+            stamina,
+            attributes: &attributes,
+            mobility,
+        };
+        Until::Rested.duration(&view, &Board::default())
+    };
+    assert_eq!(duration(&stamina), Some(Dur::ticks(3)));
+    assert!(recovered(Until::Rested, 3, &attributes) >= stamina.max - stamina.current);
+}
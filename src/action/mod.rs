@@ -1,11 +1,20 @@
+use crate::creature::PaceId;
 use crate::typical::*;
-
-pub(crate) mod on_success;
+use kinds::wait::Until;
+
+pub(crate) mod auto_move;
+pub(crate) mod command;
+pub(crate) mod duration;
+pub(crate) mod intent;
+pub(crate) mod kind;
+pub(crate) mod kinds;
+pub(crate) mod momentum;
+pub(crate) mod phase;
 pub(crate) mod systems;
-pub(crate) mod validation;
+pub(crate) mod time_advance;
+pub(crate) mod timeline;
 
 pub(crate) use systems::*;
-// pub(crate) use validation::*;
 
 #[derive(Default, Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, States)]
 pub(crate) enum ActionSystemState {
@@ -34,6 +43,7 @@ pub(crate) mod events {
     #[derive(Event, Debug, Clone)]
     pub(crate) struct ActionInvalidatedEvent {
         pub(crate) entity: Entity,
+        pub(crate) detail: ActionDetail,
     }
 
     #[derive(Event, Debug, Clone)]
@@ -44,6 +54,7 @@ pub(crate) mod events {
     #[derive(Event, Debug, Clone)]
     pub(crate) struct ActionCompleteEvent {
         pub(crate) entity: Entity,
+        pub(crate) detail: ActionDetail,
     }
 
     #[derive(Event, Debug, Clone)]
@@ -54,11 +65,22 @@ pub(crate) mod events {
     #[derive(Event, Debug, Clone)]
     pub(crate) struct ActionStartedEvent {
         pub(crate) entity: Entity,
+        pub(crate) detail: ActionDetail,
     }
 
     #[derive(Event, Debug, Clone)]
     pub(crate) struct ActionAbortedEvent {
         pub(crate) entity: Entity,
+        // None when it's a longer undertaking being called off, not one action
+        pub(crate) detail: Option<ActionDetail>,
+    }
+
+    // a monitor failed part way through an action
+    #[derive(Event, Debug, Clone)]
+    pub(crate) struct ActionInterruptedEvent {
+        pub(crate) entity: Entity,
+        pub(crate) detail: ActionDetail,
+        pub(crate) phase: &'static str,
     }
 
     #[derive(Event, Debug)]
@@ -76,6 +98,12 @@ impl Actor {
     }
 }
 
+// a glance over the shoulder
+pub(crate) const TURN_DURATION: Dur = Dur::ticks(1);
+// running costs stamina for each cell covered; anything less strenuous recovers some
+pub(crate) const RUN_STAMINA_COST: u32 = 3;
+pub(crate) const STAMINA_RECOVERY: u32 = 1;
+
 #[derive(Component, Debug, Clone, PartialEq)]
 pub(crate) struct ActorAction(pub Action);
 
@@ -84,34 +112,77 @@ pub(crate) struct Action {
     pub(crate) entity: Entity,
     pub(crate) status: ActionStatus,
     pub(crate) detail: ActionDetail,
-    pub(crate) duration: u32, // ticks
+    pub(crate) duration: Dur,
 }
 
+// durations here are nominal; each kind works out the real one on validation
 impl Action {
-    fn ticks_left(&self) -> Option<u32> {
-        if let ActionStatus::Active {
-            start_tick: _,
-            complete_tick,
-        } = self.status
-        {
-            Some(complete_tick)
-        } else {
-            None
+    pub(crate) fn walk(entity: Entity, direction: Dir) -> Self {
+        Action {
+            entity,
+            status: ActionStatus::Idle,
+            detail: ActionDetail::Move(MovementActionDetail::Walk(direction)),
+            duration: Dur::seconds(1),
+        }
+    }
+
+    pub(crate) fn turn(entity: Entity, direction: Dir) -> Self {
+        Action {
+            entity,
+            status: ActionStatus::Idle,
+            detail: ActionDetail::Move(MovementActionDetail::Turn(direction)),
+            duration: TURN_DURATION,
+        }
+    }
+
+    pub(crate) fn run(entity: Entity, direction: Dir) -> Self {
+        Action {
+            entity,
+            status: ActionStatus::Idle,
+            detail: ActionDetail::Move(MovementActionDetail::Run(direction)),
+            duration: Dur::ticks(Pace::get(PaceId::Running).ticks_per_cell()),
+        }
+    }
+
+    pub(crate) fn wait(entity: Entity, duration: Dur) -> Self {
+        Action {
+            entity,
+            status: ActionStatus::Ready, // no validation required
+            detail: ActionDetail::Wait(Until::Elapsed),
+            duration,
+        }
+    }
+
+    pub(crate) fn attack(entity: Entity, target: Entity) -> Self {
+        Action {
+            entity,
+            status: ActionStatus::Idle,
+            detail: ActionDetail::Attack(MeleeCombatActionDetail::new(entity, target)),
+            duration: Dur::seconds(1),
+        }
+    }
+
+    pub(crate) fn shoot(entity: Entity, target: Entity) -> Self {
+        Action {
+            entity,
+            status: ActionStatus::Idle,
+            detail: ActionDetail::Shoot(MissileCombatActionDetail::new(entity, target)),
+            duration: Dur::seconds(1),
         }
     }
 
     fn start(&mut self, current_tick: u32) {
         let start_tick = current_tick;
-        let complete_tick = start_tick + self.duration;
+        let complete_tick = self.duration.after(start_tick);
         self.status = ActionStatus::Active {
             start_tick,
             complete_tick,
         };
     }
 
-    // fn is_idle(&self) -> bool {
-    //     self.status == ActionStatus::Idle
-    // }
+    fn is_idle(&self) -> bool {
+        self.status == ActionStatus::Idle
+    }
 
     fn is_ready(&self) -> bool {
         self.status == ActionStatus::Ready
@@ -184,8 +255,11 @@ pub(crate) enum ActionDetail {
     Inventory(InventoryActionDetail),
     Attack(MeleeCombatActionDetail),
     Shoot(MissileCombatActionDetail),
-    Wait,
-    // General(Meta, Verb, GeneralAction),
+    Wait(kinds::wait::Until),
+    // holding readiness, to act when something happens
+    Hold(kinds::hold::HoldDetail),
+    // getting down, or back up
+    Stance(Stance),
 }
 
 #[derive(Event, Debug, PartialEq, Clone, Copy, Component)]
@@ -222,6 +296,20 @@ pub(crate) struct MeleeCombatActionDetail {
     indirect_object: Option<Entity>,
 }
 
+impl MeleeCombatActionDetail {
+    pub(crate) fn new(subject: Entity, object: Entity) -> Self {
+        MeleeCombatActionDetail {
+            subject: Some(subject),
+            object: Some(object),
+            indirect_object: None,
+        }
+    }
+
+    pub(crate) fn target(&self) -> Option<Entity> {
+        self.object
+    }
+}
+
 #[derive(Event, Debug, Eq, PartialEq, Clone, Copy, Component)]
 pub(crate) struct MissileCombatActionDetail {
     // verb: Verb,
@@ -229,3 +317,17 @@ pub(crate) struct MissileCombatActionDetail {
     object: Option<Entity>,
     indirect_object: Option<Entity>,
 }
+
+impl MissileCombatActionDetail {
+    pub(crate) fn new(subject: Entity, object: Entity) -> Self {
+        MissileCombatActionDetail {
+            subject: Some(subject),
+            object: Some(object),
+            indirect_object: None,
+        }
+    }
+
+    pub(crate) fn target(&self) -> Option<Entity> {
+        self.object
+    }
+}
@@ -0,0 +1,96 @@
+use crate::typical::*;
+
+// Momentum
+//
+// it takes a few strides to get up to speed, and once there, changing
+// direction is hard work. Each step carries over whatever of the last one's
+// velocity lies along the new heading, and adds a pace's worth of
+// acceleration - the quicker the pace, the more strides to reach it. Turning
+// sharply at speed means checking the run first, and on bad footing can end
+// with a face full of dirt.
+//
+// velocity is in cells per tick, and is kept on the Locus along with the
+// heading it was built up in. Anything but moving brings a creature to rest.
+
+// how fast the first stride from a standstill goes, as a share of full speed
+const START_SPEED: f32 = 0.5;
+// extra ticks to check a turn of 90 degrees or more, at full speed
+const SHARP_TURN_TICKS: f32 = 4.0;
+// anything slower than this can turn on a coin
+const SHARP_TURN_SPEED: f32 = 0.5;
+// to stay on one's feet turning at full speed on bad ground: agility + d10 against this
+const STUMBLE_DIFFICULTY: f32 = 10.0;
+
+fn heading(dir: Dir) -> Vec3 {
+    dir.offset().as_vec3().normalize()
+}
+
+// how much of full speed (at top_ticks per cell) is already going the new way
+fn carried(locus: &Locus, dir: Dir, top_ticks: f32) -> f32 {
+    (locus.velocity.dot(heading(dir)) * top_ticks).clamp(0.0, 1.0)
+}
+
+// how fast they're going at all, as a share of full speed
+fn speed(locus: &Locus, top_ticks: f32) -> f32 {
+    (locus.velocity.length() * top_ticks).min(1.0)
+}
+
+// share of full speed this stride reaches
+pub(crate) fn stride_speed(locus: &Locus, dir: Dir, pace: &Pace, top_ticks: f32) -> f32 {
+    // back out how far along the ramp the last stride got
+    let ramp = (carried(locus, dir, top_ticks) - START_SPEED) / (1.0 - START_SPEED);
+    let gained = 1.0 / pace.strides_to_full_speed() as f32;
+    let ramp = (ramp.max(0.0) + gained).min(1.0);
+    START_SPEED + (1.0 - START_SPEED) * ramp
+}
+
+// turning through a right angle or more
+pub(crate) fn is_sharp_turn(locus: &Locus, dir: Dir, top_ticks: f32) -> bool {
+    speed(locus, top_ticks) > SHARP_TURN_SPEED && heading(locus.direction).dot(heading(dir)) <= 0.0
+}
+
+// ticks spent checking a sharp turn
+pub(crate) fn turn_ticks(locus: &Locus, dir: Dir, top_ticks: f32) -> u32 {
+    if is_sharp_turn(locus, dir, top_ticks) {
+        (speed(locus, top_ticks) * SHARP_TURN_TICKS).round() as u32
+    } else {
+        0
+    }
+}
+
+pub(crate) fn stumbles(speed: f32, agility: u8, roll: i32) -> bool {
+    ((agility as i32 + roll) as f32) < speed * STUMBLE_DIFFICULTY
+}
+
+// how fast they're bearing down on the target, in cells per tick
+pub(crate) fn closing_speed(locus: &Locus, target: IVec3) -> f32 {
+    let toward = (target - locus.position).as_vec3().normalize_or_zero();
+    locus.velocity.dot(toward).max(0.0)
+}
+
+#[test]
+fn test_building_up_speed() {
+    let running = Pace::get(crate::creature::PaceId::Running);
+    let top_ticks = 2.0;
+    let mut locus = Locus::default();
+    let mut speeds = vec![];
+
+    // four strides east from a standstill
+    for _ in 0..4 {
+        let speed = stride_speed(&locus, Dir::East, &running, top_ticks);
+        speeds.push(speed);
+        locus.velocity = heading(Dir::East) * speed / top_ticks;
+        locus.direction = Dir::East;
+    }
+    assert_eq!(speeds[0], START_SPEED + (1.0 - START_SPEED) / 4.0);
+    assert!(speeds.windows(2).all(|w| w[1] > w[0]));
+    assert_eq!(speeds[3], 1.0);
+
+    // doubling back throws it all away, and costs a moment besides
+    assert_eq!(
+        stride_speed(&locus, Dir::West, &running, top_ticks),
+        speeds[0]
+    );
+    assert!(turn_ticks(&locus, Dir::West, top_ticks) > 0);
+    assert_eq!(turn_ticks(&locus, Dir::NorthEast, top_ticks), 0);
+}
@@ -0,0 +1,166 @@
+use crate::creature::{Condition, ConditionList};
+
+use super::kind::ActionKinds;
+
+use super::*;
+
+// Phases
+//
+// some actions are made up of several steps, one after another - firing a bow
+// means drawing an arrow, nocking it, pulling the string, aiming and letting
+// fly. Each phase takes its own share of the action's time, and has monitor
+// conditions which must hold every tick it's under way; if one fails, the
+// action is lost along with the phase it was in.
+//
+// phases are fixed for each kind of action, so they're looked up from the
+// registered kinds rather than carried around on the Action.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Monitor {
+    // not knocked out, stunned or dead
+    Conscious,
+    // not thrown off by shock, surprise or a fit
+    Composed,
+    // the target is within arm's reach
+    TargetInReach,
+    // the target can still be seen
+    TargetInSight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Phase {
+    pub(crate) name: &'static str,
+    pub(crate) ticks: u32, // nominal; scaled to fit the action's duration
+    pub(crate) monitors: &'static [Monitor],
+}
+
+pub(crate) const fn phase(name: &'static str, ticks: u32, monitors: &'static [Monitor]) -> Phase {
+    Phase {
+        name,
+        ticks,
+        monitors,
+    }
+}
+
+use Monitor::*;
+
+impl ActionDetail {
+    pub(crate) fn target(&self) -> Option<Entity> {
+        match self {
+            ActionDetail::Attack(x) => x.target(),
+            ActionDetail::Shoot(x) => x.target(),
+            _ => None,
+        }
+    }
+}
+
+// which phase is under way a number of ticks in, with phases stretched or
+// squeezed to fill the whole duration
+pub(crate) fn phase_at(
+    phases: &'static [Phase],
+    duration: u32,
+    elapsed: u32,
+) -> Option<&'static Phase> {
+    let total: u32 = phases.iter().map(|p| p.ticks).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut end = 0;
+    phases
+        .iter()
+        .find(|p| {
+            end += p.ticks;
+            elapsed < end * duration / total
+        })
+        .or(phases.last())
+}
+
+// how many ticks in each phase after the first begins
+pub(crate) fn phase_starts(phases: &'static [Phase], duration: u32) -> Vec<u32> {
+    let total: u32 = phases.iter().map(|p| p.ticks).sum();
+    if total == 0 {
+        return vec![];
+    }
+
+    let mut end = 0;
+    phases
+        .iter()
+        .take(phases.len() - 1)
+        .map(|p| {
+            end += p.ticks;
+            end * duration / total
+        })
+        .collect()
+}
+
+impl Action {
+    pub(crate) fn current_phase(
+        &self,
+        current_tick: u32,
+        kinds: &ActionKinds,
+    ) -> Option<&'static Phase> {
+        let ActionStatus::Active { start_tick, .. } = self.status else {
+            return None;
+        };
+        phase_at(
+            kinds.phases(&self.detail),
+            self.duration.as_u32(),
+            current_tick.saturating_sub(start_tick),
+        )
+    }
+}
+
+// what monitors are checked against
+pub(crate) struct MonitorContext<'a> {
+    pub(crate) locus: &'a Locus,
+    pub(crate) conditions: &'a ConditionList,
+    pub(crate) vision: &'a Vision,
+    pub(crate) target: Option<&'a Locus>,
+}
+
+impl Monitor {
+    pub(crate) fn holds(&self, ctx: &MonitorContext) -> bool {
+        let any = |conditions: &[Condition]| conditions.iter().any(|c| ctx.conditions.has(*c));
+
+        match self {
+            Conscious => !any(&[Condition::Unconscious, Condition::Stunned, Condition::Dead]),
+            Composed => !any(&[
+                Condition::Shocked,
+                Condition::Surprised,
+                Condition::Seizure,
+                Condition::Routed,
+            ]),
+            TargetInReach => ctx.target.is_some_and(|target| {
+                let d = (target.position - ctx.locus.position).abs();
+                d.x.max(d.y) <= 1 && d.z == 0
+            }),
+            TargetInSight => ctx
+                .target
+                .is_some_and(|target| ctx.vision.can_see(&target.position)),
+        }
+    }
+}
+
+// the first monitor to fail, if any
+pub(crate) fn failed_monitor(phase: &Phase, ctx: &MonitorContext) -> Option<Monitor> {
+    phase.monitors.iter().find(|m| !m.holds(ctx)).copied()
+}
+
+#[test]
+fn test_phase_at_scales_to_duration() {
+    const PHASES: [Phase; 3] = [
+        phase("draw", 6, &[]),
+        phase("nock", 4, &[]),
+        phase("release", 2, &[]),
+    ];
+
+    assert_eq!(phase_at(&PHASES, 12, 0).map(|p| p.name), Some("draw"));
+    assert_eq!(phase_at(&PHASES, 12, 6).map(|p| p.name), Some("nock"));
+    assert_eq!(phase_at(&PHASES, 12, 11).map(|p| p.name), Some("release"));
+    // twice as long: everything takes twice as long
+    assert_eq!(phase_at(&PHASES, 24, 11).map(|p| p.name), Some("draw"));
+    assert_eq!(phase_at(&PHASES, 24, 12).map(|p| p.name), Some("nock"));
+
+    assert_eq!(phase_at(&[], 10, 5), None);
+}
@@ -1,5 +1,14 @@
 use crate::input::PlayerInputState;
 
+use crate::board::flow_field::{Flow, FlowFields, FlowGoal};
+use crate::creature::ConditionList;
+
+use super::command::Travel;
+use super::duration::{Mobility, MobilityQuery};
+use super::kind::ActionKinds;
+use super::phase::{failed_monitor, MonitorContext};
+use super::time_advance::TimeAdvance;
+use super::timeline::Timeline;
 use super::*;
 
 // delete?
@@ -14,22 +23,19 @@ pub(crate) fn bootstrap(
 }
 
 type Pc = With<Player>;
-type Npc = Without<Player>;
 pub(crate) fn plan_init_check_or_tick(
     mut commands: Commands,
     mut player: Query<(Entity, &mut Actor, Option<&ActorAction>), Pc>,
-    idle_actors: Query<(Entity, &Actor), (Npc, Without<ActorAction>)>,
-    actors: Query<(Entity, &Actor, &ActorAction), Without<Player>>,
+    timeline: Res<Timeline>,
     mut input_state: ResMut<NextState<PlayerInputState>>,
     mut ev_planner: EventWriter<ActionPlanRequestEvent>,
     mut ev_tick: EventWriter<TickEvent>,
+    advance: Res<TimeAdvance>,
 ) {
-    info!(">> INIT/CHECK/TICK");
-
     let mut ready = false;
 
     if let (entity, mut actor, Some(action)) = player.single_mut() {
-        info!("player has action ({:?})", &action.0);
+        trace!("player has action ({:?})", &action.0);
 
         match action.0.status {
             ActionStatus::Ready | ActionStatus::Active { .. } => {
@@ -37,13 +43,16 @@ pub(crate) fn plan_init_check_or_tick(
                 input_state.set(PlayerInputState::Inactive);
             }
             ActionStatus::Complete => {
-                warn!("ignoring: needs to apply completed action markers");
+                trace!("ignoring: needs to apply completed action markers");
+            }
+            ActionStatus::Idle => {
+                // queued up, and waiting on validation
+                input_state.set(PlayerInputState::Inactive);
             }
-            ActionStatus::Idle => panic!("needs validation"),
             ActionStatus::Aborted => {
                 // remove & clean up. If we send an event, it'll trigger this function again
-                warn!("removing aborted command & clearing queue");
-                commands.entity(entity).remove::<ActorAction>();
+                debug!("removing aborted command & clearing queue");
+                commands.entity(entity).remove::<(ActorAction, Travel)>();
                 actor.clear_queue();
 
                 // then prepare for player input
@@ -54,19 +63,14 @@ pub(crate) fn plan_init_check_or_tick(
         input_state.set(PlayerInputState::Listen);
     }
 
-    if idle_actors.iter().count() > 0 {
-        dbg!("idle actors ..");
-        // state.set(ActionSystemState::Plan);
+    // in real time the clock doesn't wait for the player - unless it's paused
+    let ready = (ready || advance.runs_without_player()) && !advance.holds_time();
+
+    if timeline.needs_planning() {
         // TODO insert markers for them
         ev_planner.send(ActionPlanRequestEvent);
-    } else if actors.iter().all(|x| x.2 .0.is_runnable()) {
-        if ready {
-            ev_tick.send(TickEvent);
-            dbg!("TICK");
-        } else {
-            dbg!("agents ready, waiting on player");
-            // TODO something
-        }
+    } else if timeline.all_runnable() && ready {
+        ev_tick.send(TickEvent);
     }
 }
 
@@ -95,172 +99,219 @@ pub(crate) fn set_state_await_anim(
 
 pub(crate) fn handle_action_invalid(
     mut ev_invalid: EventReader<ActionInvalidatedEvent>,
+    mut ev_added: EventWriter<ActionAddedEvent>,
     mut commands: Commands,
     mut input_state: ResMut<NextState<PlayerInputState>>,
-    mut query: Query<(Entity, &mut Actor, Option<&Player>)>,
+    mut query: Query<(
+        Entity,
+        &mut Actor,
+        &Locus,
+        Option<&mut Travel>,
+        Option<&Player>,
+    )>,
+    board: Res<Board>,
 ) {
-    // warn!("HANDLER: handle_action_invalid");
-
     for ev in ev_invalid.read() {
-        let (_, mut actor, is_player) = query.get_mut(ev.entity).unwrap();
+        let Ok((entity, mut actor, locus, travel, is_player)) = query.get_mut(ev.entity) else {
+            continue;
+        };
         actor.clear_queue();
+
+        // if we were following a path, try to find another way there
+        if let Some(mut travel) = travel {
+            if let Some(action) = travel.replan(entity, locus.position, &board, &mut actor) {
+                commands.entity(entity).insert(ActorAction(action));
+                ev_added.send(ActionAddedEvent { entity });
+                continue;
+            }
+            commands.entity(entity).remove::<Travel>();
+        }
+
         if is_player.is_some() {
             input_state.set(PlayerInputState::Listen);
         } else {
-            commands.entity(ev.entity).insert(ActionPlanRequestMarker);
+            // drop the aborted action, so the planner picks something else
+            commands
+                .entity(ev.entity)
+                .remove::<ActorAction>()
+                .insert(ActionPlanRequestMarker);
         }
     }
 }
 
-pub(crate) fn apply_completed_action_markers(
+// effects have been applied by now; take up whatever's next
+pub(crate) fn apply_completed_actions(
     mut commands: Commands,
-    query: Query<(Entity, &Actor, &ActorAction)>,
+    mut query: Query<(Entity, &mut Actor, &ActorAction)>,
     mut next_state: ResMut<NextState<ActionSystemState>>,
+    mut ev_added: EventWriter<ActionAddedEvent>,
 ) {
-    warn!("APPLY COMPLETED");
-    for (entity, _actor, action) in query.iter() {
+    for (entity, mut actor, action) in query.iter_mut() {
         if action.0.is_complete() {
-            // insert the detail as a marker component
-            // we'll look for these in queries inside on_success systems
-            let mut cmds = commands.entity(entity);
-            match action.0.detail {
-                ActionDetail::Move(x) => {
-                    cmds.insert(x);
-                }
-                ActionDetail::Inventory(x) => {
-                    cmds.insert(x);
-                }
-                ActionDetail::Attack(x) => {
-                    cmds.insert(x);
-                }
-                ActionDetail::Shoot(x) => {
-                    cmds.insert(x);
-                }
-                ActionDetail::Wait => {} // noop
-            }
             commands.entity(entity).remove::<ActorAction>();
-        } else {
-            // anything else to clean up?
-            dbg!("....", action.0);
-        }
-    }
-    warn!("anim");
-    next_state.set(ActionSystemState::AwaitAnim);
-}
-
-// find the next tick in which an action completes - and advance to it
-// this makes everything feel a lot more responsive ...
-// but I'd love not to need it because it might not be workable when eg.
-// actions require monitor validations each tick
-
-#[allow(dead_code)]
-pub(crate) fn clock_tick_eager(
-    mut clock: ResMut<TickCount>,
-    frame: Res<FrameCount>,
-    query: Query<(&Actor, &ActorAction)>,
-) {
-    let mut ts: Vec<u32> = vec![];
 
-    for (_, action) in query.iter() {
-        if let Some(t) = action.0.ticks_left() {
-            ts.push(t);
+            // queued actions are validated as they come up; the board may have changed since
+            if let Some(next_action) = actor.queue.pop_front() {
+                commands.entity(entity).insert(ActorAction(next_action));
+                ev_added.send(ActionAddedEvent { entity });
+            } else {
+                commands.entity(entity).remove::<Travel>();
+            }
         }
     }
-
-    if ts.is_empty() {
-        clock.tick();
-    } else {
-        ts.sort();
-        clock.advance(ts[0]);
-    }
-
-    warn!(
-        "TICK TOCK! time is: {:?} at frame count: {:?}",
-        clock.0, frame.0,
-    );
-}
-
-// just advance by 1 tick
-pub(crate) fn clock_tick(mut clock: ResMut<TickCount>, frame: Res<FrameCount>) {
-    clock.tick();
-    warn!(
-        "TICK! currently # {:?} at frame count: {:?}",
-        clock.0, frame.0,
-    );
+    next_state.set(ActionSystemState::AwaitAnim);
 }
 
 pub(crate) fn tick_actions(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Actor, &mut ActorAction, Option<&Player>)>,
+    mut query: Query<(
+        Entity,
+        &mut Actor,
+        &mut ActorAction,
+        &Locus,
+        &ConditionList,
+        &Vision,
+        Option<&Player>,
+    )>,
+    targets: Query<&Locus>,
     mut ev_start: EventWriter<ActionStartedEvent>,
     mut ev_complete: EventWriter<ActionCompleteEvent>,
     mut ev_abort: EventWriter<ActionAbortedEvent>,
+    mut ev_interrupt: EventWriter<ActionInterruptedEvent>,
     mut ev_input: EventWriter<PlayerInputRequestEvent>,
+    mut timeline: ResMut<Timeline>,
     time: Res<TickCount>,
+    kinds: Res<ActionKinds>,
 ) {
-    // dbg!("tick actions");
+    // only those with something starting, completing or changing phase - and
+    // anyone whose monitors might have been upset by that
+    for entity in timeline.take_due(time.0) {
+        let Ok((entity, mut actor, mut a_action, locus, conditions, vision, player)) =
+            query.get_mut(entity)
+        else {
+            continue;
+        };
+
+        // monitors are re-checked for whichever phase is under way
+        if let Some(phase) = a_action.0.current_phase(time.0, &kinds) {
+            let ctx = MonitorContext {
+                locus,
+                conditions,
+                vision,
+                target: a_action.0.detail.target().and_then(|t| targets.get(t).ok()),
+            };
+            if let Some(monitor) = failed_monitor(phase, &ctx) {
+                debug!(
+                    "{:?} interrupted while in the {} phase: {:?}",
+                    entity, phase.name, monitor
+                );
+                a_action.0.status = ActionStatus::Aborted;
+                ev_interrupt.send(ActionInterruptedEvent {
+                    entity,
+                    detail: a_action.0.detail,
+                    phase: phase.name,
+                });
+            }
+        }
 
-    for (entity, mut actor, mut a_action, player) in query.iter_mut() {
         let a = a_action.0;
         if a.is_ready() {
-            dbg!("starting:", &a.status);
             a_action.0.start(time.0);
-            // warn!(
-            //     "started: {:?} {:?}",
-            //     &a_action.0.status,
-            //     &a_action.0.ticks_remaining(time.0)
-            // );
-
-            // commands.insert()
-            ev_start.send(ActionStartedEvent { entity });
-        } else if a.is_active() {
-            dbg!("status: active", &a.status);
-            // warn!("remaining: {:?}", &a.ticks_remaining(time.0));
-            if a.should_complete(time.0) {
-                // warn!("COMPLETED");
-                a_action.0.status = ActionStatus::Complete;
-                ev_complete.send(ActionCompleteEvent { entity });
+            ev_start.send(ActionStartedEvent {
+                entity,
+                detail: a.detail,
+            });
+        } else if a.is_active() && a.should_complete(time.0) {
+            a_action.0.status = ActionStatus::Complete;
+            timeline.world_changed();
+            ev_complete.send(ActionCompleteEvent {
+                entity,
+                detail: a.detail,
+            });
 
-                if let Some(mut next_action) = actor.queue.pop_front() {
-                    next_action.start(time.0);
-                    commands.entity(entity).insert(ActorAction(next_action));
-                } else if player.is_some() {
-                    ev_input.send(PlayerInputRequestEvent);
-                    info!("player has no queued action to make active");
-                } else {
-                    commands.entity(entity).insert(ActionPlanRequestMarker);
-                }
+            if !actor.queue.is_empty() {
+                // the next one is taken up once this has been applied
+            } else if player.is_some() {
+                ev_input.send(PlayerInputRequestEvent);
+                trace!("player has no queued action to make active");
             } else {
-                // dbg!("not complete yet at ", time.0);
+                commands.entity(entity).insert(ActionPlanRequestMarker);
             }
         } else if a.is_aborted() {
-            warn!("!!!! failed action, reset queue ... ");
-            commands.entity(entity).remove::<ActorAction>();
+            commands.entity(entity).remove::<(ActorAction, Travel)>();
             actor.clear_queue();
-            ev_abort.send(ActionAbortedEvent { entity });
+            ev_abort.send(ActionAbortedEvent {
+                entity,
+                detail: Some(a.detail),
+            });
+
+            if player.is_some() {
+                ev_input.send(PlayerInputRequestEvent);
+            } else {
+                commands.entity(entity).insert(ActionPlanRequestMarker);
+            }
+            timeline.world_changed();
+            continue;
         }
+
+        timeline.sync(entity, Some(&a_action.0), player.is_some(), time.0, &kinds);
     }
 }
 
 pub(crate) fn plan_agent_actions(
-    mut actors: Query<(Entity, &mut Actor, Option<&Player>), Without<ActorAction>>,
+    mut actors: Query<
+        (
+            Entity,
+            &mut Actor,
+            &Locus,
+            &Vision,
+            MobilityQuery,
+            Option<&Player>,
+        ),
+        Without<ActorAction>,
+    >,
+    player: Query<&Locus, With<Player>>,
     mut ev_added: EventWriter<ActionAddedEvent>,
     mut commands: Commands,
+    board: Res<Board>,
+    flow_fields: Res<FlowFields>,
 ) {
-    for (entity, _actor, maybe_player) in actors.iter_mut() {
+    let player_pos = player.get_single().map(|locus| locus.position).ok();
+
+    for (
+        entity,
+        _actor,
+        locus,
+        vision,
+        (pace, attributes, approach, conditions, stance),
+        maybe_player,
+    ) in actors.iter_mut()
+    {
         if maybe_player.is_some() {
-            dbg!("in plan agent actions: this is a player, NOOP");
             continue;
         }
 
-        commands.entity(entity).insert(ActorAction(Action {
-            entity,
-            status: ActionStatus::Ready, //no validation reqd
-            detail: ActionDetail::Wait,
-            duration: 10,
-        }));
+        // close in on the player when they're in sight - unless someone's in the way
+        let chase = player_pos
+            .filter(|pos| vision.can_see(pos))
+            .and_then(|_| flow_fields.step(FlowGoal::Player, Flow::Towards, &locus.position))
+            .filter(|dir| {
+                let dest = locus.position + dir.offset();
+                board.creature_store.entity_at(&dest).is_none()
+            });
+
+        if let Some(dir) = chase {
+            commands
+                .entity(entity)
+                .insert(ActorAction(Action::walk(entity, dir)));
+            ev_added.send(ActionAddedEvent { entity });
+            continue;
+        }
 
-        dbg!("added a wait action in planner");
+        let mobility = Mobility::new(pace, attributes, approach, conditions, stance);
+        commands
+            .entity(entity)
+            .insert(ActorAction(Action::wait(entity, mobility.wait_duration())));
         ev_added.send(ActionAddedEvent { entity });
     }
 }
@@ -0,0 +1,203 @@
+use crate::creature::visible_creatures;
+use crate::scheduler::Scheduler;
+
+use super::kinds::melee::AttackEvent;
+use super::timeline::Timeline;
+use super::*;
+
+// Time advance
+//
+// how the clock moves on, once everyone has something to do:
+// - PerTick: a tick at a time
+// - NextEvent: straight to the next tick where anything happens - the soonest
+//   on the timeline, something scheduled falling due, or the very next tick
+//   if the world has changed under someone's monitors.
+//   Nothing changes in between, so monitors checked then are checked
+//   everywhere they need to be.
+// - RealTime: ticks follow the wall clock at a chosen speed, whether or not
+//   the player has anything to do, and pause when something needs attention.
+//
+// none of them moves on more than a tick per frame except NextEvent, and that
+// never past a phase change, so monitors hold in every mode.
+
+// wall-clock time owed is capped, so a slow frame doesn't bring a rush of ticks
+const MAX_OWED_TICKS: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum TimeAdvanceMode {
+    #[default]
+    PerTick,
+    NextEvent,
+    RealTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum PauseTrigger {
+    EnemySpotted,
+    DamageTaken,
+    ActionInterrupted,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct TimeAdvance {
+    pub(crate) mode: TimeAdvanceMode,
+    pub(crate) ticks_per_second: f32,
+    pub(crate) paused: bool,
+    pub(crate) pause_on: HashSet<PauseTrigger>,
+    owed: f32, // ticks' worth of wall-clock time not yet spent
+}
+
+impl Default for TimeAdvance {
+    fn default() -> Self {
+        TimeAdvance {
+            mode: TimeAdvanceMode::default(),
+            ticks_per_second: 10.0,
+            paused: false,
+            pause_on: HashSet::from_iter([
+                PauseTrigger::EnemySpotted,
+                PauseTrigger::DamageTaken,
+                PauseTrigger::ActionInterrupted,
+            ]),
+            owed: 0.0,
+        }
+    }
+}
+
+impl TimeAdvance {
+    pub(crate) fn real_time(&self) -> bool {
+        self.mode == TimeAdvanceMode::RealTime
+    }
+
+    // whether the clock runs on while the player has nothing to do
+    pub(crate) fn runs_without_player(&self) -> bool {
+        self.real_time() && !self.paused
+    }
+
+    // whether the clock is stopped, even if everyone is ready
+    pub(crate) fn holds_time(&self) -> bool {
+        self.real_time() && self.paused
+    }
+
+    pub(crate) fn pause(&mut self, trigger: PauseTrigger) {
+        if self.runs_without_player() && self.pause_on.contains(&trigger) {
+            info!("paused: {:?}", trigger);
+            self.paused = true;
+            self.owed = 0.0;
+        }
+    }
+
+    pub(crate) fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.owed = 0.0;
+    }
+
+    pub(crate) fn next_mode(&mut self) {
+        self.mode = match self.mode {
+            TimeAdvanceMode::PerTick => TimeAdvanceMode::NextEvent,
+            TimeAdvanceMode::NextEvent => TimeAdvanceMode::RealTime,
+            TimeAdvanceMode::RealTime => TimeAdvanceMode::PerTick,
+        };
+        self.paused = false;
+        self.owed = 0.0;
+    }
+
+    // how many ticks to move the clock on by this frame
+    pub(crate) fn ticks_due(&mut self, delta_seconds: f32, next_event: Option<u32>) -> u32 {
+        match self.mode {
+            TimeAdvanceMode::PerTick => 1,
+            TimeAdvanceMode::NextEvent => next_event.unwrap_or(1).max(1),
+            TimeAdvanceMode::RealTime if self.paused => 0,
+            TimeAdvanceMode::RealTime => {
+                self.owed = (self.owed + delta_seconds * self.ticks_per_second).min(MAX_OWED_TICKS);
+                if self.owed >= 1.0 {
+                    self.owed -= 1.0;
+                    1
+                } else {
+                    0
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn advance_clock(
+    mut clock: ResMut<TickCount>,
+    mut advance: ResMut<TimeAdvance>,
+    timeline: Res<Timeline>,
+    scheduler: Res<Scheduler>,
+    time: Res<Time>,
+    frame: Res<FrameCount>,
+) {
+    let next_event = timeline
+        .next_tick()
+        .into_iter()
+        .chain(scheduler.next_due())
+        .chain(timeline.monitors_pending().then_some(clock.0 + 1))
+        .min()
+        .map(|tick| tick.saturating_sub(clock.0).max(1));
+    let ticks = advance.ticks_due(time.delta_seconds(), next_event);
+    clock.advance(ticks);
+
+    debug!(
+        "TICK! currently # {:?} at frame count: {:?} ({:?})",
+        clock.0, frame.0, advance.mode,
+    );
+}
+
+// in real time, go back to listening for the player after every tick
+pub(crate) fn return_to_plan_in_real_time(
+    advance: Res<TimeAdvance>,
+    mut next_state: ResMut<NextState<ActionSystemState>>,
+) {
+    if advance.real_time() {
+        next_state.set(ActionSystemState::Plan);
+    }
+}
+
+pub(crate) fn auto_pause(
+    mut advance: ResMut<TimeAdvance>,
+    mut seen: Local<HashSet<Entity>>,
+    mut ev_attack: EventReader<AttackEvent>,
+    mut ev_interrupt: EventReader<ActionInterruptedEvent>,
+    player: Query<(Entity, &Vision), With<Player>>,
+    board: Res<Board>,
+) {
+    let Ok((entity, vision)) = player.get_single() else {
+        return;
+    };
+
+    let visible = visible_creatures(vision, &board, entity);
+    if !visible.is_subset(&seen) {
+        advance.pause(PauseTrigger::EnemySpotted);
+    }
+    *seen = visible;
+
+    if ev_attack.read().any(|ev| ev.target == entity) {
+        advance.pause(PauseTrigger::DamageTaken);
+    }
+    if ev_interrupt.read().any(|ev| ev.entity == entity) {
+        advance.pause(PauseTrigger::ActionInterrupted);
+    }
+}
+
+#[test]
+fn test_real_time_pays_out_a_tick_at_a_time() {
+    let mut advance = TimeAdvance {
+        mode: TimeAdvanceMode::RealTime,
+        ..default()
+    };
+
+    assert_eq!(advance.ticks_due(0.05, Some(8)), 0);
+    assert_eq!(advance.ticks_due(0.05, Some(8)), 1);
+    // a long frame doesn't make up all the time lost
+    assert_eq!(advance.ticks_due(5.0, Some(8)), 1);
+    assert_eq!(advance.ticks_due(0.0, Some(8)), 1);
+    assert_eq!(advance.ticks_due(0.0, Some(8)), 0);
+
+    advance.toggle_pause();
+    assert_eq!(advance.ticks_due(1.0, Some(8)), 0);
+
+    advance.mode = TimeAdvanceMode::NextEvent;
+    assert_eq!(advance.ticks_due(0.0, Some(8)), 8);
+    assert_eq!(advance.ticks_due(0.0, None), 1);
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeSet;
+
+use super::kind::ActionKinds;
+use super::phase::phase_starts;
+use super::*;
+
+// Timeline
+//
+// the next tick each actor needs looking at - when their action starts,
+// moves into a new phase or completes - kept in order, so the clock can jump
+// straight to the soonest and only those due get looked at. Anyone else is
+// in the middle of something, and nothing about them changes until then;
+// the one exception is monitors, which can fail whenever the world changes
+// around them, so anyone in a monitored action is looked at again after
+// anything completes, a condition wears off or something scheduled goes off.
+//
+// alongside, it keeps track of who's holding things up: creatures with
+// nothing to do, and those whose next action hasn't been validated yet.
+//
+// actions change all over the place, so rather than every system keeping it
+// up to date, it's brought into line with whatever changed since it last
+// looked - at the start of every frame, and wherever the action loop needs
+// it to be current.
+
+#[derive(Resource, Debug, Clone, Default)]
+pub(crate) struct Timeline {
+    due: BTreeSet<(u32, Entity)>,
+    next: HashMap<Entity, u32>,
+    // in an action with monitors, which need checking when the world changes
+    monitored: HashSet<Entity>,
+    // NPCs without an action, who need a plan
+    unplanned: HashSet<Entity>,
+    // NPCs whose action can't run yet
+    pending: HashSet<Entity>,
+    // something's changed since monitors were last checked
+    world_changed: bool,
+}
+
+impl Timeline {
+    fn schedule(&mut self, entity: Entity, tick: u32) {
+        self.unschedule(entity);
+        self.due.insert((tick, entity));
+        self.next.insert(entity, tick);
+    }
+
+    fn unschedule(&mut self, entity: Entity) {
+        if let Some(tick) = self.next.remove(&entity) {
+            self.due.remove(&(tick, entity));
+        }
+    }
+
+    fn forget(&mut self, entity: Entity) {
+        self.unschedule(entity);
+        self.monitored.remove(&entity);
+        self.unplanned.remove(&entity);
+        self.pending.remove(&entity);
+    }
+
+    // bring one actor into line with the action they have now, if any
+    pub(crate) fn sync(
+        &mut self,
+        entity: Entity,
+        action: Option<&Action>,
+        is_player: bool,
+        now: u32,
+        kinds: &ActionKinds,
+    ) {
+        self.forget(entity);
+
+        let Some(action) = action else {
+            if !is_player {
+                self.unplanned.insert(entity);
+            }
+            return;
+        };
+        match action.status {
+            // to be started - or cleaned up - as soon as the clock moves
+            ActionStatus::Ready | ActionStatus::Aborted => self.schedule(entity, now),
+            ActionStatus::Active { .. } => {
+                let tick = next_relevant_tick(action, now, kinds).unwrap_or(now);
+                self.schedule(entity, tick);
+                if kinds
+                    .phases(&action.detail)
+                    .iter()
+                    .any(|p| !p.monitors.is_empty())
+                {
+                    self.monitored.insert(entity);
+                }
+            }
+            ActionStatus::Idle | ActionStatus::Complete => {}
+        }
+        if !is_player && !action.is_runnable() {
+            self.pending.insert(entity);
+        }
+    }
+
+    // the soonest tick anyone needs looking at
+    pub(crate) fn next_tick(&self) -> Option<u32> {
+        self.due.first().map(|(tick, _)| *tick)
+    }
+
+    // everyone to look at this tick, in a settled order; those due come off
+    // the timeline, and go back on once looked at
+    pub(crate) fn take_due(&mut self, now: u32) -> Vec<Entity> {
+        let mut entities = vec![];
+        while let Some(&(tick, entity)) = self.due.first() {
+            if tick > now {
+                break;
+            }
+            self.unschedule(entity);
+            entities.push(entity);
+        }
+        if std::mem::take(&mut self.world_changed) {
+            let mut monitored: Vec<Entity> = self
+                .monitored
+                .iter()
+                .filter(|e| !entities.contains(e))
+                .copied()
+                .collect();
+            monitored.sort();
+            entities.extend(monitored);
+        }
+        entities
+    }
+
+    pub(crate) fn world_changed(&mut self) {
+        self.world_changed = true;
+    }
+
+    // monitors to look at again, as soon as the clock moves
+    pub(crate) fn monitors_pending(&self) -> bool {
+        self.world_changed && !self.monitored.is_empty()
+    }
+
+    pub(crate) fn needs_planning(&self) -> bool {
+        !self.unplanned.is_empty()
+    }
+
+    pub(crate) fn all_runnable(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+// the first tick after now at which an action starts a phase, or completes
+fn next_relevant_tick(action: &Action, now: u32, kinds: &ActionKinds) -> Option<u32> {
+    match action.status {
+        ActionStatus::Active {
+            start_tick,
+            complete_tick,
+        } => phase_starts(kinds.phases(&action.detail), action.duration.as_u32())
+            .into_iter()
+            .map(|offset| start_tick.saturating_add(offset))
+            .chain([complete_tick])
+            .filter(|tick| *tick > now)
+            .min(),
+        _ => None,
+    }
+}
+
+// catches up with every action added, changed or removed since last time
+pub(crate) fn sync_timeline(
+    mut timeline: ResMut<Timeline>,
+    changed: Query<
+        (Entity, Option<&ActorAction>, Has<Player>),
+        Or<(Added<Actor>, Changed<ActorAction>)>,
+    >,
+    actors: Query<(Option<&ActorAction>, Has<Player>), With<Actor>>,
+    mut removed_actions: RemovedComponents<ActorAction>,
+    mut removed_actors: RemovedComponents<Actor>,
+    kinds: Res<ActionKinds>,
+    tick: Res<TickCount>,
+) {
+    for (entity, action, is_player) in changed.iter() {
+        timeline.sync(entity, action.map(|a| &a.0), is_player, tick.0, &kinds);
+    }
+    for entity in removed_actions.read() {
+        if let Ok((action, is_player)) = actors.get(entity) {
+            timeline.sync(entity, action.map(|a| &a.0), is_player, tick.0, &kinds);
+        }
+    }
+    for entity in removed_actors.read() {
+        timeline.forget(entity);
+    }
+}
+
+#[test]
+fn test_only_the_due_are_looked_at() {
+    let kinds = ActionKinds::default();
+    let e = |id: u32| Entity::from_raw(id);
+    let mut timeline = Timeline::default();
+
+    let mut walk = Action::walk(e(1), Dir::East);
+    walk.duration = Dur::seconds(1);
+    walk.start(0);
+    let mut wait = Action::wait(e(2), Dur::seconds(3));
+    wait.start(0);
+
+    timeline.sync(e(1), Some(&walk), false, 0, &kinds);
+    timeline.sync(e(2), Some(&wait), false, 0, &kinds);
+    timeline.sync(e(3), None, false, 0, &kinds);
+
+    assert_eq!(timeline.next_tick(), Some(10));
+    assert!(timeline.take_due(9).is_empty());
+    assert_eq!(timeline.take_due(10), [e(1)]);
+    assert!(timeline.needs_planning());
+    assert!(timeline.all_runnable());
+
+    // the walk's done; a new one comes up, waiting on validation
+    timeline.sync(
+        e(1),
+        Some(&Action::walk(e(1), Dir::East)),
+        false,
+        10,
+        &kinds,
+    );
+    assert!(!timeline.all_runnable());
+    assert_eq!(timeline.next_tick(), Some(30));
+}
@@ -1,5 +0,0 @@
-use super::*;
-
-pub(crate) mod movement;
-
-pub(crate) use movement::*;
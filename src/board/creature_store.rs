@@ -20,7 +20,11 @@ impl CreatureStore {
     }
 
     pub fn update(&mut self, entity: Entity, pos: IVec3) {
-        self.to_pos.insert(entity, pos);
+        if let Some(old) = self.to_pos.insert(entity, pos) {
+            if self.to_entity.get(&old) == Some(&entity) {
+                self.to_entity.remove(&old);
+            }
+        }
         self.to_entity.insert(pos, entity);
     }
 
@@ -31,4 +35,8 @@ impl CreatureStore {
     pub fn pos_for(&self, entity: &Entity) -> Option<&IVec3> {
         self.to_pos.get(entity)
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&IVec3, &Entity)> {
+        self.to_entity.iter()
+    }
 }
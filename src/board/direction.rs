@@ -58,12 +58,23 @@ impl Dir {
         DIRECTIONS[(Self::DIRECTION_NUM + self as usize - n) % Self::DIRECTION_NUM]
     }
 
+    pub fn opposite(self) -> Self {
+        self.clockwise_neighbour(Self::DIRECTION_NUM / 2)
+    }
+
+    pub fn from_offset(offset: IVec3) -> Option<Dir> {
+        DIRECTION_OFFSETS
+            .iter()
+            .position(|o| *o == offset)
+            .map(|i| DIRECTIONS[i])
+    }
+
     pub fn to_degrees(self) -> f32 {
-        DIRECTION_RADIANS[self as usize] * TAU
+        COMPASS_DEGREES[self as usize]
     }
 
     pub fn to_radians(self) -> f32 {
-        DIRECTION_RADIANS[self as usize]
+        DIRECTION_TURNS[self as usize] * TAU
     }
 
     pub fn closest_cardinal(degrees: f32) -> Dir {
@@ -117,8 +128,8 @@ pub const DIRECTION_OFFSETS: [IVec3; 8] = [
     IVec3 { x: -1, y: 1, z: 0 },
 ];
 
-// * TAU = degrees
-pub const DIRECTION_RADIANS: [f32; 8] = [0.0, 0.125, 0.25, 0.375, 0.5, 0.625, 0.75, 0.875];
+// fractions of a turn, clockwise from North; * TAU = radians
+pub const DIRECTION_TURNS: [f32; 8] = [0.0, 0.125, 0.25, 0.375, 0.5, 0.625, 0.75, 0.875];
 
 pub const COMPASS_DEGREES: [f32; 8] = [0., 45., 90., 135., 180., 225., 270., 315.];
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
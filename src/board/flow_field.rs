@@ -0,0 +1,528 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::typical::*;
+
+use super::direction::DIRECTIONS;
+use super::TerrainChangedEvent;
+
+// Dijkstra maps & flow fields
+//
+// rather than each creature running A* every time it moves, compute the distance
+// from every cell to a set of goals once, and let any number of creatures roll
+// downhill on it. Fleeing works the same way, on an inverted map which prefers
+// open ground over dead ends.
+//
+// Fields are cached for the level. When a goal moves or the terrain changes,
+// only what depended on it is worked out again: whatever got its distance by
+// way of the changed cells is cleared and filled back in from around the hole,
+// and anything which can now do better is lowered from there. The flee map
+// follows the distances which changed, and flow directions are looked at
+// again only around cells whose distance did.
+//
+// https://www.roguebasin.com/index.php/The_Incredible_Power_of_Dijkstra_Maps
+// https://www.redblobgames.com/pathfinding/tower-defense/
+
+// > 1 makes fleeing creatures prefer to run past the threat towards open space
+const FLEE_COEFFICIENT: f32 = 1.2;
+
+fn flee_seed(distance: i32) -> i32 {
+    -(distance as f32 * FLEE_COEFFICIENT) as i32
+}
+
+// a cell and its neighbours
+fn around(cells: impl IntoIterator<Item = IVec3>) -> HashSet<IVec3> {
+    cells
+        .into_iter()
+        .flat_map(|pos| {
+            DIRECTIONS
+                .iter()
+                .map(move |dir| pos + dir.offset())
+                .chain([pos])
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DijkstraMap {
+    distances: HashMap<IVec3, i32>,
+    // where it spreads out from, and starting at what
+    seeds: HashMap<IVec3, i32>,
+}
+
+impl DijkstraMap {
+    fn from_seeds(board: &Board, seeds: HashMap<IVec3, i32>) -> Self {
+        let mut map = DijkstraMap { seeds, ..default() };
+        let seeds: Vec<(IVec3, i32)> = map
+            .seeds
+            .keys()
+            .filter_map(|pos| map.seed(board, pos).map(|d| (*pos, d)))
+            .collect();
+        map.relax(board, seeds);
+        map
+    }
+
+    pub(crate) fn towards(board: &Board, goals: &[IVec3]) -> Self {
+        DijkstraMap::from_seeds(board, goals.iter().map(|pos| (*pos, 0)).collect())
+    }
+
+    pub(crate) fn flee(&self, board: &Board) -> Self {
+        let seeds = self
+            .distances
+            .iter()
+            .map(|(pos, d)| (*pos, flee_seed(*d)))
+            .collect();
+        DijkstraMap::from_seeds(board, seeds)
+    }
+
+    pub(crate) fn distance(&self, pos: &IVec3) -> Option<i32> {
+        self.distances.get(pos).copied()
+    }
+
+    // a seed only counts where it can be stood on
+    fn seed(&self, board: &Board, pos: &IVec3) -> Option<i32> {
+        board.terrain_cost(pos)?;
+        self.seeds.get(pos).copied()
+    }
+
+    // the neighbouring step which gets closest to the goal
+    pub(crate) fn downhill(&self, board: &Board, pos: &IVec3) -> Option<Dir> {
+        let here = self.distance(pos)?;
+        DIRECTIONS
+            .iter()
+            .filter_map(|dir| {
+                board.terrain_step_cost(pos, *dir)?;
+                let d = self.distance(&(*pos + dir.offset()))?;
+                Some((d, *dir))
+            })
+            .filter(|(d, _)| *d < here)
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, dir)| dir)
+    }
+
+    // Dijkstra outwards from the seeds. Only ever lowers distances, so it can
+    // be used to patch part of an existing map; hands back what each cell it
+    // set was before.
+    fn relax(
+        &mut self,
+        board: &Board,
+        seeds: impl IntoIterator<Item = (IVec3, i32)>,
+    ) -> HashMap<IVec3, Option<i32>> {
+        let mut frontier: BinaryHeap<(Reverse<i32>, [i32; 3])> = BinaryHeap::new();
+        let mut before: HashMap<IVec3, Option<i32>> = HashMap::new();
+
+        for (pos, d) in seeds {
+            if self.distance(&pos).is_some_and(|e| e < d) {
+                continue;
+            }
+            before.entry(pos).or_insert(self.distance(&pos));
+            self.distances.insert(pos, d);
+            frontier.push((Reverse(d), pos.to_array()));
+        }
+
+        while let Some((Reverse(d), pos)) = frontier.pop() {
+            let pos = IVec3::from_array(pos);
+            if self.distance(&pos).is_some_and(|e| e < d) {
+                continue; // stale
+            }
+
+            for dir in DIRECTIONS {
+                let next = pos + dir.offset();
+                if board.terrain_cost(&next).is_none() {
+                    continue;
+                }
+                // the cost of stepping from the neighbour back towards here
+                let Some(step) = board.terrain_step_cost(&next, dir.opposite()) else {
+                    continue;
+                };
+                let candidate = d + step as i32;
+                if self.distance(&next).is_some_and(|e| e <= candidate) {
+                    continue;
+                }
+                before.entry(next).or_insert(self.distance(&next));
+                self.distances.insert(next, candidate);
+                frontier.push((Reverse(candidate), next.to_array()));
+            }
+        }
+        before
+    }
+
+    // neighbours which have a distance, keyed on it
+    fn reached_around(&self, pos: IVec3) -> Vec<(Reverse<i32>, [i32; 3])> {
+        DIRECTIONS
+            .iter()
+            .map(|dir| pos + dir.offset())
+            .filter_map(|next| self.distance(&next).map(|d| (Reverse(d), next.to_array())))
+            .collect()
+    }
+
+    // whether a cell's distance still stands without the affected cells: it's
+    // a seed, or one step from a neighbour which got there another way
+    fn supported(&self, board: &Board, pos: &IVec3, affected: &HashSet<IVec3>) -> bool {
+        let Some(d) = self.distance(pos) else {
+            return true;
+        };
+        self.seed(board, pos) == Some(d)
+            || DIRECTIONS.iter().any(|dir| {
+                let next = *pos + dir.offset();
+                !affected.contains(&next)
+                    && board
+                        .terrain_step_cost(pos, *dir)
+                        .zip(self.distance(&next))
+                        .is_some_and(|(step, e)| e + step as i32 == d)
+            })
+    }
+
+    // something about these cells has changed - the terrain, or their seed.
+    // Everything which got its distance by way of them is cleared, nearest
+    // first, and filled back in from around the hole; anything which can now
+    // do better is lowered from there. Hands back every cell whose distance
+    // changed.
+    fn repair(&mut self, board: &Board, changed: &[IVec3]) -> HashSet<IVec3> {
+        let mut affected: HashSet<IVec3> = changed.iter().copied().collect();
+        // looked at in order of distance, so anything a cell might have got
+        // its distance through has already been settled one way or the other
+        let mut candidates: BinaryHeap<(Reverse<i32>, [i32; 3])> = changed
+            .iter()
+            .flat_map(|pos| self.reached_around(*pos))
+            .collect();
+        while let Some((_, pos)) = candidates.pop() {
+            let pos = IVec3::from_array(pos);
+            if affected.contains(&pos) || self.supported(board, &pos, &affected) {
+                continue;
+            }
+            affected.insert(pos);
+            candidates.extend(self.reached_around(pos));
+        }
+
+        let mut before: HashMap<IVec3, Option<i32>> = affected
+            .iter()
+            .map(|pos| (*pos, self.distances.remove(pos)))
+            .collect();
+        let seeds: Vec<(IVec3, i32)> = affected
+            .iter()
+            .flat_map(|pos| {
+                let own = self.seed(board, pos).map(|d| (*pos, d));
+                let edge = DIRECTIONS
+                    .iter()
+                    .map(|dir| *pos + dir.offset())
+                    .filter(|next| !affected.contains(next))
+                    .filter_map(|next| self.distance(&next).map(|d| (next, d)))
+                    .collect::<Vec<_>>();
+                own.into_iter().chain(edge)
+            })
+            .collect();
+        for (pos, d) in self.relax(board, seeds) {
+            before.entry(pos).or_insert(d);
+        }
+
+        before
+            .into_iter()
+            .filter(|(pos, d)| self.distance(pos) != *d)
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct FlowField {
+    directions: HashMap<IVec3, Dir>,
+}
+
+impl FlowField {
+    pub(crate) fn new(board: &Board, map: &DijkstraMap) -> Self {
+        let mut field = FlowField::default();
+        field.update(board, map, map.distances.keys().copied());
+        field
+    }
+
+    // look again at which way is downhill, for just these cells
+    fn update(&mut self, board: &Board, map: &DijkstraMap, cells: impl IntoIterator<Item = IVec3>) {
+        for pos in cells {
+            match map.downhill(board, &pos) {
+                Some(dir) => self.directions.insert(pos, dir),
+                None => self.directions.remove(&pos),
+            };
+        }
+    }
+
+    pub(crate) fn step(&self, pos: &IVec3) -> Option<Dir> {
+        self.directions.get(pos).copied()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum FlowGoal {
+    Player,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Flow {
+    Towards,
+    #[allow(dead_code)] // nothing runs away yet
+    Away,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Field {
+    goals: Vec<IVec3>,
+    towards: DijkstraMap,
+    away: DijkstraMap,
+    towards_flow: FlowField,
+    away_flow: FlowField,
+}
+
+impl Field {
+    fn new(board: &Board, goals: Vec<IVec3>) -> Self {
+        let towards = DijkstraMap::towards(board, &goals);
+        let away = towards.flee(board);
+        Field {
+            goals,
+            towards_flow: FlowField::new(board, &towards),
+            away_flow: FlowField::new(board, &away),
+            towards,
+            away,
+        }
+    }
+
+    // only the goals which came or went are looked at
+    fn set_goals(&mut self, board: &Board, goals: Vec<IVec3>) -> HashSet<IVec3> {
+        let changed: Vec<IVec3> = self
+            .goals
+            .iter()
+            .filter(|pos| !goals.contains(pos))
+            .chain(goals.iter().filter(|pos| !self.goals.contains(pos)))
+            .copied()
+            .collect();
+        for pos in self.goals.iter() {
+            self.towards.seeds.remove(pos);
+        }
+        for pos in goals.iter() {
+            self.towards.seeds.insert(*pos, 0);
+        }
+        self.goals = goals;
+        self.repair(board, &changed)
+    }
+
+    // patches both maps and both flows for a change at these cells; hands
+    // back where the distance to the goals changed
+    fn repair(&mut self, board: &Board, changed: &[IVec3]) -> HashSet<IVec3> {
+        let moved = self.towards.repair(board, changed);
+
+        for pos in moved.iter() {
+            match self.towards.distance(pos) {
+                Some(d) => self.away.seeds.insert(*pos, flee_seed(d)),
+                None => self.away.seeds.remove(pos),
+            };
+        }
+        let roots: Vec<IVec3> = moved.iter().chain(changed).copied().collect();
+        let fled = self.away.repair(board, &roots);
+
+        // a step's cost changes around changed terrain, and which way is
+        // downhill around any cell whose distance changed
+        let towards_cells = around(moved.iter().chain(changed).copied());
+        self.towards_flow
+            .update(board, &self.towards, towards_cells);
+        let away_cells = around(fled.into_iter().chain(changed.iter().copied()));
+        self.away_flow.update(board, &self.away, away_cells);
+
+        moved
+    }
+
+    #[allow(dead_code)]
+    fn map(&self, flow: Flow) -> &DijkstraMap {
+        match flow {
+            Flow::Towards => &self.towards,
+            Flow::Away => &self.away,
+        }
+    }
+
+    fn flow(&self, flow: Flow) -> &FlowField {
+        match flow {
+            Flow::Towards => &self.towards_flow,
+            Flow::Away => &self.away_flow,
+        }
+    }
+}
+
+// cached for the current level
+#[derive(Resource, Debug, Default)]
+pub(crate) struct FlowFields {
+    fields: HashMap<FlowGoal, Field>,
+}
+
+impl FlowFields {
+    pub(crate) fn set_goals(&mut self, board: &Board, goal: FlowGoal, goals: Vec<IVec3>) {
+        match self.fields.get_mut(&goal) {
+            Some(field) if field.goals == goals => {}
+            Some(field) => {
+                field.set_goals(board, goals);
+            }
+            None => {
+                self.fields.insert(goal, Field::new(board, goals));
+            }
+        }
+    }
+
+    pub(crate) fn step(&self, goal: FlowGoal, flow: Flow, pos: &IVec3) -> Option<Dir> {
+        self.fields.get(&goal)?.flow(flow).step(pos)
+    }
+
+    // for the tests; creatures only need to know which way to go
+    #[allow(dead_code)]
+    pub(crate) fn distance(&self, goal: FlowGoal, flow: Flow, pos: &IVec3) -> Option<i32> {
+        self.fields.get(&goal)?.map(flow).distance(pos)
+    }
+
+    pub(crate) fn terrain_changed(&mut self, board: &Board, pos: IVec3) {
+        for field in self.fields.values_mut() {
+            field.repair(board, &[pos]);
+        }
+    }
+}
+
+pub(crate) fn update_flow_fields(
+    board: Res<Board>,
+    mut fields: ResMut<FlowFields>,
+    mut ev_terrain: EventReader<TerrainChangedEvent>,
+    player: Query<&Locus, With<Player>>,
+) {
+    for TerrainChangedEvent(pos) in ev_terrain.read() {
+        fields.terrain_changed(&board, *pos);
+    }
+
+    if let Ok(locus) = player.get_single() {
+        fields.set_goals(&board, FlowGoal::Player, vec![locus.position]);
+    }
+}
+
+#[test]
+fn test_dijkstra_map_downhill() {
+    let board = Board::default();
+    let goal = IVec3::new(10, 10, 0);
+    let map = DijkstraMap::towards(&board, &[goal]);
+
+    assert_eq!(map.distance(&goal), Some(0));
+    assert_eq!(map.distance(&IVec3::new(13, 10, 0)), Some(30));
+    assert_eq!(
+        map.downhill(&board, &IVec3::new(13, 10, 0)),
+        Some(Dir::West)
+    );
+    assert_eq!(
+        map.downhill(&board, &IVec3::new(12, 12, 0)),
+        Some(Dir::SouthWest)
+    );
+    assert_eq!(map.downhill(&board, &goal), None);
+
+    let away = map.flee(&board);
+    let from = IVec3::new(13, 10, 0);
+    let dir = away.downhill(&board, &from).unwrap();
+    assert!(map.distance(&(from + dir.offset())) > map.distance(&from));
+}
+
+#[test]
+fn test_flow_field_patched_when_wall_removed() {
+    let mut board = Board::default();
+    for y in 0..board.size.y {
+        board
+            .wall_store
+            .set(IVec3::new(5, y, 0), Entity::from_raw(y as u32));
+    }
+
+    let mut fields = FlowFields::default();
+    let goal = IVec3::new(2, 2, 0);
+    let beyond = IVec3::new(8, 2, 0);
+    fields.set_goals(&board, FlowGoal::Player, vec![goal]);
+    assert_eq!(
+        fields.distance(FlowGoal::Player, Flow::Towards, &beyond),
+        None
+    );
+
+    let gap = IVec3::new(5, 2, 0);
+    board.wall_store.remove(&gap);
+    fields.terrain_changed(&board, gap);
+
+    assert_eq!(
+        fields.distance(FlowGoal::Player, Flow::Towards, &beyond),
+        Some(60)
+    );
+    assert_eq!(
+        fields.step(FlowGoal::Player, Flow::Towards, &beyond),
+        Some(Dir::West)
+    );
+}
+
+#[test]
+fn test_flow_fields_follow_the_walls() {
+    let mut app = App::new();
+    app.init_resource::<Board>()
+        .init_resource::<FlowFields>()
+        .add_event::<TerrainChangedEvent>()
+        .add_systems(
+            Update,
+            (super::announce_terrain_changes, update_flow_fields).chain(),
+        );
+    let goal = IVec3::new(2, 2, 0);
+    app.world.spawn((
+        Player,
+        Locus {
+            position: goal,
+            ..default()
+        },
+    ));
+    let beyond = IVec3::new(8, 2, 0);
+    let distance = |app: &App| {
+        app.world
+            .resource::<FlowFields>()
+            .distance(FlowGoal::Player, Flow::Towards, &beyond)
+    };
+
+    app.update();
+    assert_eq!(distance(&app), Some(60));
+
+    // wall it off, then knock a hole through
+    let mut board = app.world.resource_mut::<Board>();
+    for y in 0..BOARD_SIZE_Y {
+        board.set_wall(IVec3::new(5, y, 0), Entity::from_raw(y as u32));
+    }
+    app.update();
+    assert_eq!(distance(&app), None);
+
+    app.world
+        .resource_mut::<Board>()
+        .remove_wall(&IVec3::new(5, 2, 0));
+    app.update();
+    assert_eq!(distance(&app), Some(60));
+}
+
+#[test]
+fn test_only_the_affected_region_is_recomputed() {
+    let mut board = Board::default();
+    let mut field = Field::new(&board, vec![IVec3::new(2, 2, 0)]);
+
+    // a wall well east of the goal can only matter to what's behind it
+    let mut changed = HashSet::new();
+    for y in 5..20 {
+        let pos = IVec3::new(25, y, 0);
+        board.set_wall(pos, Entity::from_raw(y as u32));
+        changed.extend(field.repair(&board, &[pos]));
+    }
+    assert!(!changed.is_empty());
+    assert!(changed.iter().all(|pos| pos.x >= 25));
+    assert!(changed.contains(&IVec3::new(26, 12, 0)));
+
+    let fresh = Field::new(&board, field.goals.clone());
+    assert_eq!(field.towards, fresh.towards);
+    assert_eq!(field.away, fresh.away);
+    assert_eq!(field.towards_flow, fresh.towards_flow);
+    assert_eq!(field.away_flow, fresh.away_flow);
+
+    // and a goal which moves a step is patched up the same
+    let goals = vec![IVec3::new(3, 2, 0)];
+    assert!(!field.set_goals(&board, goals.clone()).is_empty());
+    let fresh = Field::new(&board, goals);
+    assert_eq!(field.towards, fresh.towards);
+    assert_eq!(field.away, fresh.away);
+    assert_eq!(field.towards_flow, fresh.towards_flow);
+    assert_eq!(field.away_flow, fresh.away_flow);
+}
@@ -32,6 +32,23 @@ impl PartialOrd for Room {
 const EDGE: i32 = 2;
 const MARGIN: i32 = 2;
 
+// how far below ground the level being built is; only the surface, at 0, is
+// open to the sky. The dig starts a level down.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Depth(pub(crate) u32);
+
+impl Default for Depth {
+    fn default() -> Self {
+        Depth(1)
+    }
+}
+
+impl Depth {
+    pub(crate) fn is_surface(&self) -> bool {
+        self.0 == 0
+    }
+}
+
 impl Room {
     fn random(rng: &mut RngComponent) -> Self {
         let width = rng.i32(6..12);
@@ -162,6 +179,7 @@ pub(crate) fn populate_board(
     mut board: ResMut<Board>,
     mut ev_writer: EventWriter<SpawnPlayerEvent>,
     mut global_rng: ResMut<GlobalChaChaRng>,
+    depth: Res<Depth>,
 ) {
     let mut rng = RngComponent::from(&mut global_rng);
     let mut rooms: Vec<Room> = vec![];
@@ -180,6 +198,8 @@ pub(crate) fn populate_board(
 
     rooms.sort();
 
+    board.open_sky = depth.is_surface();
+
     // place Player in first room
     let fst = rooms.first().expect("can't play without a player ...");
     let initial = IVec3::new(fst.x + 1, fst.y + 1, 0);
@@ -211,7 +231,7 @@ pub(crate) fn populate_board(
                 } else {
                     let wall = Wall::new(x, y, z, Substance::Dirt);
                     entity = chunk.spawn((cell, floor, wall)).id();
-                    board.wall_store.set(*pos, entity);
+                    board.set_wall(*pos, entity);
                     true
                 };
 
@@ -2,36 +2,17 @@
 
 use crate::typical::*;
 
-use super::{BOARD_SIZE_X, BOARD_SIZE_Y};
+use super::shape::{self, Bounds};
 
 // https://www.redblobgames.com/grids/circle-drawing/
 // https://www.redblobgames.com/coordinates/axes-and-angles/#angles
 // https://www.redblobgames.com/grids/line-drawing/
 
 #[allow(dead_code)]
-pub fn circle(centre: IVec3, radius: f32) -> HashSet<[i32; 2]> {
-    let mut circle: HashSet<[i32; 2]> = HashSet::new();
-
-    let [x, y, _z] = centre.to_array();
-    let [fx, fy, r] = [x as f32, y as f32, radius]; // r = x.5 looks better
-    let r2 = r * r;
-
-    let top = fy - r;
-    let bot = fy + r;
-
-    for y in f32::round(top) as i32..f32::round(bot) as i32 {
-        let dy = y as f32 - fy;
-        let dx = f32::sqrt(r2 - dy * dy);
-        let left = f32::ceil(fx - dx);
-        let right = f32::floor(fx + dx);
-
-        for x in f32::round(left) as i32..f32::round(right) as i32 {
-            if x >= 0 && y >= 0 && x <= BOARD_SIZE_X && y <= BOARD_SIZE_Y {
-                circle.insert([x, y]);
-            }
-        }
-    }
-    circle
+pub fn circle(bounds: Bounds, centre: IVec3, radius: f32) -> HashSet<[i32; 2]> {
+    shape::circle(bounds, centre, radius)
+        .map(|pos| [pos.x, pos.y])
+        .collect()
 }
 
 #[allow(dead_code)]
@@ -68,17 +49,16 @@ pub fn take_sector(
 
 #[allow(dead_code)]
 pub fn sector_facing(facing: Dir, centre: &IVec3, circle: HashSet<[i32; 2]>) -> HashSet<[i32; 2]> {
-    let angle = f32::to_radians(COMPASS_DEGREES[facing as usize]);
-    take_sector(angle, f32::to_radians(90.), centre, circle)
+    take_sector(facing.to_radians(), f32::to_radians(90.), centre, circle)
 }
 
 #[allow(dead_code)]
-pub fn fov_facing(centre: &IVec3, facing: Dir, radius: f32) -> HashSet<[i32; 2]> {
+pub fn fov_facing(bounds: Bounds, centre: &IVec3, facing: Dir, radius: f32) -> HashSet<[i32; 2]> {
     take_sector(
-        facing.to_degrees(),
+        facing.to_radians(),
         f32::to_radians(120.),
         centre,
-        circle(*centre, radius),
+        circle(bounds, *centre, radius),
     )
 }
 
@@ -0,0 +1,95 @@
+use crate::typical::*;
+
+use super::shape;
+
+// Light
+//
+// how brightly each cell is lit, from 0 (pitch black) to FULL_LIGHT -
+// enough to make out anything within sight. Underground, there's no ambient
+// light: only what creatures carry, and what burns on the walls. Under an
+// open sky, there's daylight - and at night, the stars.
+
+pub(crate) const FULL_LIGHT: u8 = 10;
+const STARLIGHT: u8 = 1;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LightSource {
+    pub(crate) intensity: u8,
+    pub(crate) radius: f32,
+}
+
+impl LightSource {
+    pub(crate) fn torch() -> Self {
+        LightSource {
+            intensity: 8,
+            radius: 8.5,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Default)]
+pub(crate) struct LightMap {
+    pub(crate) ambient: u8,
+    levels: HashMap<IVec3, u8>,
+}
+
+impl LightMap {
+    pub(crate) fn level_at(&self, pos: &IVec3) -> u8 {
+        self.levels.get(pos).copied().unwrap_or(0).max(self.ambient)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.levels.clear();
+    }
+
+    // light falls off linearly with distance, and doesn't pass through walls
+    pub(crate) fn illuminate(&mut self, board: &Board, origin: IVec3, source: &LightSource) {
+        for pos in shape::circle(board.bounds(), origin, source.radius) {
+            if !board.has_line_of_sight(origin, pos) {
+                continue;
+            }
+            let falloff = shape::distance_squared(origin, pos).sqrt() / source.radius;
+            let level = (source.intensity as f32 * (1.0 - falloff)).round() as u8;
+            let lit = self.levels.entry(pos).or_insert(0);
+            *lit = lit.saturating_add(level).min(FULL_LIGHT);
+        }
+    }
+}
+
+pub(crate) fn update_light_map(
+    board: Res<Board>,
+    mut light_map: ResMut<LightMap>,
+    sources: Query<(&Locus, &LightSource)>,
+    clock: Res<Clock>,
+) {
+    light_map.clear();
+    light_map.ambient = if board.open_sky {
+        ((clock.daylight() * FULL_LIGHT as f32).round() as u8).max(STARLIGHT)
+    } else {
+        0
+    };
+    for (locus, source) in sources.iter() {
+        light_map.illuminate(&board, locus.position, source);
+    }
+}
+
+#[test]
+fn test_daylight_only_under_open_sky() {
+    let mut app = App::new();
+    app.init_resource::<Board>()
+        .init_resource::<LightMap>()
+        .init_resource::<Clock>()
+        .add_systems(Update, update_light_map);
+    let at = |hours: u32| Clock::new(TickCount(Dur::hours(hours).as_u32()));
+    let ambient = |app: &mut App, clock: Clock| {
+        app.insert_resource(clock);
+        app.update();
+        app.world.resource::<LightMap>().ambient
+    };
+
+    assert_eq!(ambient(&mut app, at(12)), 0);
+
+    app.world.resource_mut::<Board>().open_sky = true;
+    assert_eq!(ambient(&mut app, at(12)), FULL_LIGHT);
+    assert_eq!(ambient(&mut app, at(0)), STARLIGHT);
+}
@@ -2,9 +2,13 @@ pub(crate) mod cell;
 pub(crate) mod cell_store;
 pub(crate) mod creature_store;
 pub(crate) mod direction;
+pub(crate) mod flow_field;
 pub(crate) mod generator;
 pub(crate) mod geometry;
+pub(crate) mod light;
+pub(crate) mod pathfinding;
 pub(crate) mod primitives;
+pub(crate) mod shape;
 pub(crate) use cell_store::*;
 pub(crate) use creature_store::*;
 
@@ -17,6 +21,10 @@ use crate::typical::*;
 
 type Size3d = IVec3;
 
+// sent whenever a wall or feature is added or removed - see Board::set_wall
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct TerrainChangedEvent(pub IVec3);
+
 // Board
 //
 #[derive(Clone, Debug, Resource)]
@@ -30,6 +38,10 @@ pub(crate) struct Board {
     pub(crate) visibility_store: EntityPositionStore,
     pub(crate) items_store: EntityPositionStore,
     pub(crate) creature_store: CreatureStore,
+    // a surface level, lit by the sun - or not
+    pub(crate) open_sky: bool,
+    // walls and features added or removed, and not yet announced
+    terrain_changes: Vec<IVec3>,
 }
 
 impl Default for Board {
@@ -47,6 +59,8 @@ impl Default for Board {
             items_store: EntityPositionStore::default(),
             visibility_store: EntityPositionStore::default(),
             creature_store: CreatureStore::default(),
+            open_sky: false,
+            terrain_changes: vec![],
         }
     }
 }
@@ -64,6 +78,10 @@ impl Board {
         cv
     }
 
+    pub(crate) fn bounds(&self) -> shape::Bounds {
+        shape::Bounds::new(self.size)
+    }
+
     pub(crate) fn apply_direction(&self, pos: &IVec3, direction: &Dir) -> Result<IVec3, &str> {
         let [x, y, z] = pos.to_array();
         let [dx, dy, dz] = direction.offset().to_array();
@@ -82,4 +100,53 @@ impl Board {
     pub(crate) fn is_unoccupied(&self, pos: &IVec3) -> bool {
         self.wall_store.get(pos).is_none()
     }
+
+    // nothing opaque between the two cells; either end may itself be a wall
+    pub(crate) fn has_line_of_sight(&self, from: IVec3, to: IVec3) -> bool {
+        shape::line(self.bounds(), from, to)
+            .filter(|pos| *pos != from && *pos != to)
+            .all(|pos| self.wall_store.get(&pos).is_none())
+    }
+
+    // walls and features go in and out through these, so that everything
+    // worked out from the terrain hears about it
+    pub(crate) fn set_wall(&mut self, pos: IVec3, entity: Entity) {
+        self.wall_store.set(pos, entity);
+        self.terrain_changes.push(pos);
+    }
+
+    pub(crate) fn remove_wall(&mut self, pos: &IVec3) -> Option<Entity> {
+        let removed = self.wall_store.remove(pos);
+        if removed.is_some() {
+            self.terrain_changes.push(*pos);
+        }
+        removed
+    }
+
+    pub(crate) fn set_feature(&mut self, pos: IVec3, entity: Entity) {
+        self.feature_store.set(pos, entity);
+        self.terrain_changes.push(pos);
+    }
+
+    pub(crate) fn remove_feature(&mut self, pos: &IVec3) -> Option<Entity> {
+        let removed = self.feature_store.remove(pos);
+        if removed.is_some() {
+            self.terrain_changes.push(*pos);
+        }
+        removed
+    }
+}
+
+// sends a TerrainChangedEvent for every cell changed since last time
+pub(crate) fn announce_terrain_changes(
+    mut board: ResMut<Board>,
+    mut ev_terrain: EventWriter<TerrainChangedEvent>,
+) {
+    if board.terrain_changes.is_empty() {
+        return;
+    }
+    let mut changes = std::mem::take(&mut board.terrain_changes);
+    changes.sort_by_key(|pos| pos.to_array());
+    changes.dedup();
+    ev_terrain.send_batch(changes.into_iter().map(TerrainChangedEvent));
 }
@@ -0,0 +1,188 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::typical::*;
+
+use super::direction::DIRECTIONS;
+
+// Pathfinding
+//
+// A* over the board, in 8 directions. Costs are in tenths of a cell, so a
+// diagonal step is 14 and a straight one 10.
+//
+// https://www.redblobgames.com/pathfinding/a-star/introduction.html
+// https://github.com/TheAlgorithms/Rust/blob/master/src/graph/astar.rs
+
+pub(crate) const STRAIGHT_COST: u32 = 10;
+pub(crate) const DIAGONAL_COST: u32 = 14;
+
+// creatures move, so a cell they're standing in isn't impassable - just best avoided
+pub(crate) const OCCUPIED_PENALTY: u32 = 40;
+
+// give up rather than search the whole level for something unreachable
+const MAX_EXPANSIONS: usize = 4096;
+
+impl Board {
+    // multiplier for the cost of entering a cell; None if it can't be entered at all
+    pub(crate) fn terrain_cost(&self, pos: &IVec3) -> Option<u32> {
+        if !self.bounds().contains(pos) || !self.is_unoccupied(pos) {
+            None
+        } else if self.feature_store.get(pos).is_some() {
+            Some(2)
+        } else {
+            Some(1)
+        }
+    }
+
+    // cost of stepping from one cell into its neighbour, ignoring who's in the way
+    pub(crate) fn terrain_step_cost(&self, from: &IVec3, dir: Dir) -> Option<u32> {
+        let terrain = self.terrain_cost(&(*from + dir.offset()))?;
+
+        let base = if is_diagonal(dir) {
+            // no squeezing between two walls meeting at a corner
+            let [a, b] = dir.arc_vectors(1);
+            let blocked = |v: Vec2| !self.is_unoccupied(&(*from + v.as_ivec2().extend(0)));
+            if blocked(a) && blocked(b) {
+                return None;
+            }
+            DIAGONAL_COST
+        } else {
+            STRAIGHT_COST
+        };
+
+        Some(base * terrain)
+    }
+
+    // cost of stepping from one cell into its neighbour, for a given creature
+    pub(crate) fn step_cost(&self, from: &IVec3, dir: Dir, mover: Option<Entity>) -> Option<u32> {
+        let terrain = self.terrain_step_cost(from, dir)?;
+
+        let occupied = match self.creature_store.entity_at(&(*from + dir.offset())) {
+            Some(e) if Some(*e) != mover => OCCUPIED_PENALTY,
+            _ => 0,
+        };
+
+        Some(terrain + occupied)
+    }
+}
+
+// returns the cells to step through, excluding the start and including the goal
+pub(crate) fn astar(
+    board: &Board,
+    start: IVec3,
+    goal: IVec3,
+    mover: Option<Entity>,
+) -> Option<Vec<IVec3>> {
+    if start == goal {
+        return Some(vec![]);
+    }
+    board.terrain_cost(&goal)?;
+
+    let mut frontier: BinaryHeap<(Reverse<u32>, [i32; 3])> = BinaryHeap::new();
+    let mut came_from: HashMap<IVec3, IVec3> = HashMap::new();
+    let mut cost_so_far: HashMap<IVec3, u32> = HashMap::new();
+    let mut expansions = 0;
+
+    frontier.push((Reverse(0), start.to_array()));
+    cost_so_far.insert(start, 0);
+
+    while let Some((_, current)) = frontier.pop() {
+        let current = IVec3::from_array(current);
+        if current == goal {
+            return Some(reconstruct(&came_from, start, goal));
+        }
+
+        expansions += 1;
+        if expansions > MAX_EXPANSIONS {
+            break;
+        }
+
+        for dir in DIRECTIONS {
+            let Some(step) = board.step_cost(&current, dir, mover) else {
+                continue;
+            };
+            let next = current + dir.offset();
+            let cost = cost_so_far[&current] + step;
+
+            if cost_so_far.get(&next).is_some_and(|c| *c <= cost) {
+                continue;
+            }
+            cost_so_far.insert(next, cost);
+            came_from.insert(next, current);
+            let priority = cost + octile_distance(next, goal);
+            frontier.push((Reverse(priority), next.to_array()));
+        }
+    }
+    None
+}
+
+fn reconstruct(came_from: &HashMap<IVec3, IVec3>, start: IVec3, goal: IVec3) -> Vec<IVec3> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while let Some(prev) = came_from.get(&current) {
+        if *prev == start {
+            break;
+        }
+        path.push(*prev);
+        current = *prev;
+    }
+    path.reverse();
+    path
+}
+
+// admissible heuristic for 8-way movement
+pub(crate) fn octile_distance(a: IVec3, b: IVec3) -> u32 {
+    let d = (b - a).abs();
+    let (long, short) = (d.x.max(d.y) as u32, d.x.min(d.y) as u32);
+    STRAIGHT_COST * (long - short) + DIAGONAL_COST * short
+}
+
+fn is_diagonal(dir: Dir) -> bool {
+    let o = dir.offset();
+    o.x != 0 && o.y != 0
+}
+
+// the direction of each step along a path
+pub(crate) fn path_directions(start: IVec3, path: &[IVec3]) -> Vec<Dir> {
+    let mut prev = start;
+    path.iter()
+        .filter_map(|pos| {
+            let dir = Dir::from_offset(*pos - prev);
+            prev = *pos;
+            dir
+        })
+        .collect()
+}
+
+#[test]
+fn test_astar_open_ground() {
+    let board = Board::default();
+    let path = astar(&board, IVec3::new(1, 1, 0), IVec3::new(5, 3, 0), None).unwrap();
+
+    assert_eq!(path.len(), 4);
+    assert_eq!(path.last(), Some(&IVec3::new(5, 3, 0)));
+}
+
+#[test]
+fn test_astar_around_walls() {
+    let mut board = Board::default();
+    // a wall with a gap at the top
+    for y in 0..10 {
+        board
+            .wall_store
+            .set(IVec3::new(5, y, 0), Entity::from_raw(y as u32));
+    }
+
+    let start = IVec3::new(2, 2, 0);
+    let goal = IVec3::new(8, 2, 0);
+    let path = astar(&board, start, goal, None).unwrap();
+
+    assert!(path.iter().all(|p| board.is_unoccupied(p)));
+    assert!(path.iter().any(|p| p.y >= 10));
+    assert_eq!(path_directions(start, &path).len(), path.len());
+
+    board
+        .wall_store
+        .set(IVec3::new(8, 2, 0), Entity::from_raw(99));
+    assert_eq!(astar(&board, start, goal, None), None);
+}
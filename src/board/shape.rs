@@ -0,0 +1,227 @@
+use std::f32::consts::TAU;
+
+use crate::typical::*;
+
+use super::direction::CARDINAL_DIRECTIONS;
+
+// Shapes
+//
+// areas of effect for spells, explosions, gas, cleave attacks, etc.
+// every shape is a lazy iterator of the cells it covers, clipped to the bounds
+// of the board it's cast on. Shapes are flat: they cover the z-plane of their origin.
+//
+// https://www.redblobgames.com/grids/circle-drawing/
+// https://www.redblobgames.com/grids/line-drawing/
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Bounds {
+    pub(crate) min: IVec3,
+    pub(crate) max: IVec3, // exclusive
+}
+
+impl Bounds {
+    pub(crate) fn new(size: IVec3) -> Self {
+        Bounds {
+            min: IVec3::ZERO,
+            max: size,
+        }
+    }
+
+    pub(crate) fn contains(&self, pos: &IVec3) -> bool {
+        pos.cmpge(self.min).all() && pos.cmplt(self.max).all()
+    }
+}
+
+// every cell between two corners, inclusive
+pub(crate) fn rect(bounds: Bounds, a: IVec3, b: IVec3) -> impl Iterator<Item = IVec3> {
+    let min = a.min(b).max(bounds.min);
+    let max = a.max(b).min(bounds.max - IVec3::ONE);
+    let z = a.z;
+
+    (min.y..=max.y)
+        .flat_map(move |y| (min.x..=max.x).map(move |x| IVec3::new(x, y, z)))
+        .filter(move |pos| bounds.contains(pos))
+}
+
+// r = x.5 looks better
+pub(crate) fn circle(bounds: Bounds, centre: IVec3, radius: f32) -> impl Iterator<Item = IVec3> {
+    let r2 = radius * radius;
+    let reach = radius.ceil() as i32;
+    let reach = IVec3::new(reach, reach, 0);
+
+    rect(bounds, centre - reach, centre + reach)
+        .filter(move |pos| distance_squared(centre, *pos) <= r2)
+}
+
+// everything within outer, and beyond inner
+#[allow(dead_code)]
+pub(crate) fn ring(
+    bounds: Bounds,
+    centre: IVec3,
+    inner: f32,
+    outer: f32,
+) -> impl Iterator<Item = IVec3> {
+    let inner2 = inner * inner;
+    circle(bounds, centre, outer).filter(move |pos| distance_squared(centre, *pos) > inner2)
+}
+
+// facing and width are in radians, clockwise from North
+pub(crate) fn cone(
+    bounds: Bounds,
+    origin: IVec3,
+    facing: f32,
+    width: f32,
+    radius: f32,
+) -> impl Iterator<Item = IVec3> {
+    circle(bounds, origin, radius)
+        .filter(move |pos| *pos == origin || within_arc(bearing(origin, *pos), facing, width))
+}
+
+// nothing aims by compass point yet
+#[allow(dead_code)]
+pub(crate) fn cone_facing(
+    bounds: Bounds,
+    origin: IVec3,
+    facing: Dir,
+    width: f32,
+    radius: f32,
+) -> impl Iterator<Item = IVec3> {
+    cone(bounds, origin, facing.to_radians(), width, radius)
+}
+
+// from one cell to another, inclusive; stops at the edge of the board
+pub(crate) fn line(bounds: Bounds, from: IVec3, to: IVec3) -> impl Iterator<Item = IVec3> {
+    let n = diagonal_distance(from, to);
+
+    (0..=n)
+        .map(move |step| {
+            let t = if n == 0 { 0.0 } else { step as f32 / n as f32 };
+            from.as_vec3().lerp(to.as_vec3(), t).round().as_ivec3()
+        })
+        .take_while(move |pos| bounds.contains(pos))
+}
+
+// a circle which spreads out from its centre and doesn't pass through walls.
+// waiting on something which goes bang
+#[allow(dead_code)]
+pub(crate) fn blast(
+    board: &Board,
+    centre: IVec3,
+    radius: f32,
+) -> FloodFill<impl Fn(&IVec3) -> bool + '_> {
+    let r2 = radius * radius;
+    flood_fill(board.bounds(), centre, move |pos: &IVec3| {
+        distance_squared(centre, *pos) <= r2 && board.is_unoccupied(pos)
+    })
+}
+
+// every cell reachable from start through cells which pass the test.
+// spreads orthogonally, so it won't leak through the corners where walls meet.
+pub(crate) fn flood_fill<F>(bounds: Bounds, start: IVec3, passable: F) -> FloodFill<F>
+where
+    F: Fn(&IVec3) -> bool,
+{
+    let mut fill = FloodFill {
+        bounds,
+        passable,
+        frontier: VecDeque::new(),
+        seen: HashSet::new(),
+    };
+
+    if bounds.contains(&start) && (fill.passable)(&start) {
+        fill.seen.insert(start);
+        fill.frontier.push_back(start);
+    }
+    fill
+}
+
+pub(crate) struct FloodFill<F> {
+    bounds: Bounds,
+    passable: F,
+    frontier: VecDeque<IVec3>,
+    seen: HashSet<IVec3>,
+}
+
+impl<F> Iterator for FloodFill<F>
+where
+    F: Fn(&IVec3) -> bool,
+{
+    type Item = IVec3;
+
+    fn next(&mut self) -> Option<IVec3> {
+        let pos = self.frontier.pop_front()?;
+
+        for dir in CARDINAL_DIRECTIONS {
+            let next = pos + dir.offset();
+            if self.bounds.contains(&next) && !self.seen.contains(&next) && (self.passable)(&next) {
+                self.seen.insert(next);
+                self.frontier.push_back(next);
+            }
+        }
+        Some(pos)
+    }
+}
+
+// util
+
+pub(crate) fn distance_squared(a: IVec3, b: IVec3) -> f32 {
+    (b - a).truncate().as_vec2().length_squared()
+}
+
+pub(crate) fn diagonal_distance(a: IVec3, b: IVec3) -> i32 {
+    let d = (b - a).abs();
+    i32::max(d.x, d.y)
+}
+
+// radians, clockwise from North, in 0..TAU
+pub(crate) fn bearing(from: IVec3, to: IVec3) -> f32 {
+    let d = (to - from).as_vec3();
+    f32::atan2(d.x, d.y).rem_euclid(TAU)
+}
+
+pub(crate) fn within_arc(angle: f32, facing: f32, width: f32) -> bool {
+    let delta = (angle - facing).rem_euclid(TAU);
+    delta <= width / 2.0 || delta >= TAU - width / 2.0
+}
+
+#[test]
+fn test_shapes_clip_to_bounds() {
+    let bounds = Bounds::new(IVec3::new(10, 10, 1));
+
+    assert_eq!(circle(bounds, IVec3::new(5, 5, 0), 1.0).count(), 5);
+    assert_eq!(circle(bounds, IVec3::ZERO, 1.0).count(), 3);
+    assert_eq!(
+        rect(bounds, IVec3::new(-4, -4, 0), IVec3::new(1, 1, 0)).count(),
+        4
+    );
+    assert_eq!(ring(bounds, IVec3::new(5, 5, 0), 1.0, 1.5).count(), 4);
+    assert!(line(bounds, IVec3::new(5, 5, 0), IVec3::new(15, 5, 0)).all(|p| p.x < 10));
+}
+
+#[test]
+fn test_cone_facing() {
+    let bounds = Bounds::new(IVec3::new(10, 10, 1));
+    let origin = IVec3::new(5, 5, 0);
+    let cells: Vec<IVec3> = cone_facing(bounds, origin, Dir::East, TAU / 4.0, 3.0).collect();
+
+    assert!(cells.contains(&IVec3::new(8, 5, 0)));
+    assert!(cells.contains(&IVec3::new(7, 6, 0)));
+    assert!(!cells.contains(&IVec3::new(3, 5, 0)));
+    assert!(!cells.contains(&IVec3::new(5, 8, 0)));
+}
+
+#[test]
+fn test_blast_stops_at_walls() {
+    let mut board = Board::default();
+    // a wall running North / South, with the blast on its West side
+    for y in 0..board.size.y {
+        board
+            .wall_store
+            .set(IVec3::new(6, y, 0), Entity::from_raw(y as u32));
+    }
+
+    let cells: Vec<IVec3> = blast(&board, IVec3::new(4, 4, 0), 4.0).collect();
+
+    assert!(cells.contains(&IVec3::new(5, 4, 0)));
+    assert!(cells.iter().all(|p| p.x < 6));
+}
@@ -5,16 +5,16 @@ use bevy::prelude::Component;
 #[derive(Component, Debug, Clone, Copy)]
 #[allow(dead_code)]
 pub struct Primary {
-    dexterity: u8,
-    agility: u8,
-    resilience: u8,
-    speed: u8,
-    power: u8,
-    will: u8,
-    intuition: u8,
-    magnetism: u8,
-    perception: u8,
-    acuity: u8,
+    pub dexterity: u8,
+    pub agility: u8,
+    pub resilience: u8,
+    pub speed: u8,
+    pub power: u8,
+    pub will: u8,
+    pub intuition: u8,
+    pub magnetism: u8,
+    pub perception: u8,
+    pub acuity: u8,
 }
 impl Primary {
     fn random() -> Primary {
@@ -37,11 +37,11 @@ impl Primary {
 #[derive(Component, Debug, Clone, Copy)]
 #[allow(dead_code)]
 pub struct Secondary {
-    stamina: u8,
-    reflexes: u8,
-    composure: u8,
-    stride: f64,   // square per tick at Relaxed pace
-    recovery: f64, // stamina per tick at rest
+    pub stamina: u8,
+    pub reflexes: u8,
+    pub composure: u8,
+    pub stride: f64,   // square per tick at Relaxed pace
+    pub recovery: f64, // stamina per tick at rest
 }
 
 impl Secondary {
@@ -59,8 +59,8 @@ impl Secondary {
 #[derive(Component, Debug, Clone)]
 #[allow(dead_code)]
 pub struct Attributes {
-    primary: Primary,
-    secondary: Secondary,
+    pub primary: Primary,
+    pub secondary: Secondary,
     // all this shit should be part of PlayerBundle, right?
     // stance: Stance,
     // facing: Direction,
@@ -104,4 +104,23 @@ impl Attributes {
             // anatomy: (),
         }
     }
+
+    // every primary attribute the same - for creatures which behave predictably
+    pub fn uniform(value: u8) -> Attributes {
+        let primary = Primary {
+            dexterity: value,
+            agility: value,
+            resilience: value,
+            speed: value,
+            power: value,
+            will: value,
+            intuition: value,
+            magnetism: value,
+            perception: value,
+            acuity: value,
+        };
+        let secondary = Secondary::new(&primary);
+
+        Attributes { primary, secondary }
+    }
 }
@@ -0,0 +1,250 @@
+use crate::typical::*;
+
+use super::{Attributes, SkillList};
+
+// Skill checks
+//
+// roll under: (d12 * 2) + d10 against a target number of
+//
+//   primary stat + half a secondary stat + skill + advantages + difficulty
+//
+// where the difficulty is a step on the ladder below, from foolproof down to
+// impossible. The roll runs 3 to 34 and is even about 18.5, so a target of
+// 18 is a coin toss. Anything rolled at or under the target succeeds, by as
+// much as it was under; the d12 at its kindest (1) on a success, or cruellest
+// (12) on a failure, makes it a critical one.
+//
+// see notes/rpg_sys.md
+
+const D12_SIDES: u8 = 12;
+const D10_SIDES: u8 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Attribute {
+    Dexterity,
+    Agility,
+    Resilience,
+    Speed,
+    Power,
+    Will,
+    Intuition,
+    Magnetism,
+    Perception,
+    Acuity,
+    // worked out from the others
+    Stamina,
+    Reflexes,
+    Composure,
+}
+
+impl Attribute {
+    pub(crate) fn of(&self, attributes: &Attributes) -> u8 {
+        let (p, s) = (&attributes.primary, &attributes.secondary);
+        match self {
+            Attribute::Dexterity => p.dexterity,
+            Attribute::Agility => p.agility,
+            Attribute::Resilience => p.resilience,
+            Attribute::Speed => p.speed,
+            Attribute::Power => p.power,
+            Attribute::Will => p.will,
+            Attribute::Intuition => p.intuition,
+            Attribute::Magnetism => p.magnetism,
+            Attribute::Perception => p.perception,
+            Attribute::Acuity => p.acuity,
+            Attribute::Stamina => s.stamina,
+            Attribute::Reflexes => s.reflexes,
+            Attribute::Composure => s.composure,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Difficulty {
+    Foolproof,
+    Routine,
+    Easy,
+    Simple,
+    Moderate,
+    #[default]
+    Tricky,
+    Hard,
+    Challenging,
+    Harrowing,
+    Desperate,
+    Impossible,
+}
+
+impl Difficulty {
+    // added to the target number
+    pub(crate) fn modifier(&self) -> i32 {
+        match self {
+            Difficulty::Foolproof => 18,
+            Difficulty::Routine => 15,
+            Difficulty::Easy => 13,
+            Difficulty::Simple => 10,
+            Difficulty::Moderate => 7,
+            Difficulty::Tricky => 4,
+            Difficulty::Hard => 1,
+            Difficulty::Challenging => -2,
+            Difficulty::Harrowing => -5,
+            Difficulty::Desperate => -8,
+            Difficulty::Impossible => -12,
+        }
+    }
+}
+
+// what's being tried, and how hard it is
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Check {
+    pub(crate) primary: Attribute,
+    // counts for half
+    pub(crate) secondary: Attribute,
+    pub(crate) skill: fn(&SkillList) -> u8,
+    // tools, position, help - or the lack of them
+    pub(crate) advantages: i32,
+    pub(crate) difficulty: Difficulty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CheckRoll {
+    pub(crate) d12: u8,
+    pub(crate) d10: u8,
+}
+
+impl CheckRoll {
+    pub(crate) fn total(&self) -> i32 {
+        self.d12 as i32 * 2 + self.d10 as i32
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CheckResult {
+    pub(crate) roll: CheckRoll,
+    pub(crate) target: i32,
+    pub(crate) success: bool,
+    // how far under the target (or, negative, over it)
+    pub(crate) margin: i32,
+    pub(crate) critical: bool,
+}
+
+impl Check {
+    pub(crate) fn new(
+        primary: Attribute,
+        secondary: Attribute,
+        skill: fn(&SkillList) -> u8,
+    ) -> Self {
+        Check {
+            primary,
+            secondary,
+            skill,
+            advantages: 0,
+            difficulty: Difficulty::default(),
+        }
+    }
+
+    pub(crate) fn with_difficulty(self, difficulty: Difficulty) -> Self {
+        Check { difficulty, ..self }
+    }
+
+    pub(crate) fn with_advantages(self, advantages: i32) -> Self {
+        Check { advantages, ..self }
+    }
+
+    pub(crate) fn target(&self, attributes: &Attributes, skills: &SkillList) -> i32 {
+        self.primary.of(attributes) as i32
+            + self.secondary.of(attributes) as i32 / 2
+            + (self.skill)(skills) as i32
+            + self.advantages
+            + self.difficulty.modifier()
+    }
+
+    pub(crate) fn roll(
+        &self,
+        attributes: &Attributes,
+        skills: &SkillList,
+        rng: &mut RngComponent,
+    ) -> CheckResult {
+        let roll = CheckRoll {
+            d12: rng.u8(1..=D12_SIDES),
+            d10: rng.u8(1..=D10_SIDES),
+        };
+        resolve(roll, self.target(attributes, skills))
+    }
+
+    // chance of success, 0 to 1, for showing before anyone commits
+    pub(crate) fn probability(&self, attributes: &Attributes, skills: &SkillList) -> f32 {
+        success_chance(self.target(attributes, skills))
+    }
+}
+
+pub(crate) fn resolve(roll: CheckRoll, target: i32) -> CheckResult {
+    let margin = target - roll.total();
+    let success = margin >= 0;
+    let critical = if success {
+        roll.d12 == 1
+    } else {
+        roll.d12 == D12_SIDES
+    };
+    CheckResult {
+        roll,
+        target,
+        success,
+        margin,
+        critical,
+    }
+}
+
+// every way the dice can fall is as likely as any other, so count them
+pub(crate) fn success_chance(target: i32) -> f32 {
+    let succeeding = (1..=D12_SIDES)
+        .flat_map(|d12| (1..=D10_SIDES).map(move |d10| CheckRoll { d12, d10 }))
+        .filter(|roll| roll.total() <= target)
+        .count();
+    succeeding as f32 / (D12_SIDES as u32 * D10_SIDES as u32) as f32
+}
+
+#[test]
+fn test_roll_under_the_target() {
+    let mut attributes = Attributes::uniform(5);
+    let mut skills = SkillList::default();
+    skills.sneak = 5;
+    let sneak = Check::new(Attribute::Agility, Attribute::Perception, |s| s.sneak);
+
+    // 5 + 5 / 2 + 5, on the baseline
+    assert_eq!(sneak.target(&attributes, &skills), 16);
+    attributes.primary.agility = 7;
+    assert_eq!(sneak.target(&attributes, &skills), 18);
+    assert_eq!(sneak.probability(&attributes, &skills), 0.5);
+
+    let harder = sneak.with_difficulty(Difficulty::Impossible);
+    let easier = sneak
+        .with_difficulty(Difficulty::Foolproof)
+        .with_advantages(2);
+    assert!(harder.probability(&attributes, &skills) < 0.5);
+    assert_eq!(easier.probability(&attributes, &skills), 1.0);
+    assert_eq!(success_chance(2), 0.0);
+
+    let lucky = resolve(CheckRoll { d12: 1, d10: 3 }, 18);
+    assert_eq!(
+        (lucky.success, lucky.margin, lucky.critical),
+        (true, 13, true)
+    );
+    let clumsy = resolve(CheckRoll { d12: 12, d10: 1 }, 18);
+    assert_eq!(
+        (clumsy.success, clumsy.margin, clumsy.critical),
+        (false, -7, true)
+    );
+    assert!(!resolve(CheckRoll { d12: 9, d10: 1 }, 18).critical);
+
+    // the same seed, the same luck
+    let rolls = |seed| {
+        let mut rng = RngComponent::with_seed(seed);
+        (0..20)
+            .map(|_| sneak.roll(&attributes, &skills, &mut rng))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(rolls(7), rolls(7));
+    assert!(rolls(7)
+        .iter()
+        .all(|r| r.margin == r.target - r.roll.total()));
+}
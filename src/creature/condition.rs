@@ -1,24 +1,114 @@
 #![allow(dead_code)]
 
+use serde::Deserialize;
+
+use crate::action::timeline::Timeline;
 use crate::typical::*;
 // Condition
 //
+// some conditions wear off by themselves, after however long the data file
+// says; the rest stay until something deals with them.
+
+const CONDITION_DURATIONS: &str = include_str!("../../assets/data/conditions.ron");
+
 #[derive(Component, Debug, Clone, Default, Eq, PartialEq)]
 pub struct ConditionList {
     needs: (),
-    conditions: (),
+    // and the tick each wears off, if it does
+    conditions: HashMap<Condition, Option<u32>>,
     injuries: (),
-    encumberance: (),
+    encumbrance: u8, // percent of what can be carried without strain
 }
 
 #[allow(dead_code)]
 impl ConditionList {
+    pub fn has(&self, condition: Condition) -> bool {
+        self.conditions.contains_key(&condition)
+    }
+
+    pub fn add(&mut self, condition: Condition) {
+        self.conditions.insert(condition, None);
+    }
+
+    // for a while; catching it again while it lasts only ever draws it out
+    pub(crate) fn add_for(&mut self, condition: Condition, duration: Dur, now: u32) {
+        let until = duration.after(now);
+        let lasts = self.conditions.entry(condition).or_insert(Some(until));
+        if let Some(tick) = lasts {
+            *tick = until.max(*tick);
+        }
+    }
+
+    // for as long as that sort of thing lasts
+    pub(crate) fn inflict(
+        &mut self,
+        condition: Condition,
+        durations: &ConditionDurations,
+        now: u32,
+    ) {
+        match durations.of(condition) {
+            Some(duration) => self.add_for(condition, duration, now),
+            None => self.add(condition),
+        }
+    }
+
+    pub fn remove(&mut self, condition: Condition) {
+        self.conditions.remove(&condition);
+    }
+
+    // None for anything that isn't going away by itself
+    pub(crate) fn remaining(&self, condition: Condition, now: u32) -> Option<Dur> {
+        self.conditions
+            .get(&condition)
+            .copied()
+            .flatten()
+            .map(|tick| Dur::between(now, tick))
+    }
+
+    pub(crate) fn has_lapsed(&self, now: u32) -> bool {
+        self.conditions.values().flatten().any(|tick| *tick <= now)
+    }
+
+    pub(crate) fn expire(&mut self, now: u32) {
+        self.conditions
+            .retain(|_, until| until.map_or(true, |tick| tick > now));
+    }
+
+    pub fn encumbrance(&self) -> u8 {
+        self.encumbrance
+    }
+
+    pub fn set_encumbrance(&mut self, percent: u8) {
+        self.encumbrance = percent;
+    }
+}
+
+// how long each condition lasts, from the data file
+#[derive(Resource, Debug, Clone)]
+pub(crate) struct ConditionDurations(HashMap<Condition, Dur>);
+
+impl Default for ConditionDurations {
     fn default() -> Self {
-        ConditionList {
-            needs: (),
-            conditions: (),
-            injuries: (),
-            encumberance: (),
+        ConditionDurations(ron::from_str(CONDITION_DURATIONS).expect("bad conditions.ron"))
+    }
+}
+
+impl ConditionDurations {
+    pub(crate) fn of(&self, condition: Condition) -> Option<Dur> {
+        self.0.get(&condition).copied()
+    }
+}
+
+// only those with something wearing off are touched, so nothing else looks changed
+pub(crate) fn expire_conditions(
+    mut query: Query<&mut ConditionList>,
+    mut timeline: ResMut<Timeline>,
+    tick: Res<TickCount>,
+) {
+    for mut conditions in query.iter_mut() {
+        if conditions.has_lapsed(tick.0) {
+            conditions.expire(tick.0);
+            timeline.world_changed();
         }
     }
 }
@@ -38,7 +128,7 @@ pub enum Need {
     Greed,
 }
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize)]
 pub enum Condition {
     Blind,
     Deaf,
@@ -135,3 +225,27 @@ pub enum WoundSeverity {
 }
 
 // Injury Severity Score - AIS for 3 most severely injured parts of the body
+
+#[test]
+fn test_conditions_wear_off() {
+    let durations = ConditionDurations::default();
+    assert_eq!(durations.of(Condition::Stunned), Some(Dur::seconds(6)));
+    assert_eq!(durations.of(Condition::Blind), None);
+
+    let mut conditions = ConditionList::default();
+    conditions.inflict(Condition::Stunned, &durations, 100);
+    conditions.inflict(Condition::Blind, &durations, 100);
+    let stunned_until = Dur::seconds(6).after(100);
+    assert_eq!(
+        conditions.remaining(Condition::Stunned, 100),
+        Some(Dur::seconds(6))
+    );
+    assert_eq!(conditions.remaining(Condition::Blind, 100), None);
+
+    // stunned again, briefly: it doesn't cut the first one short
+    conditions.add_for(Condition::Stunned, Dur::seconds(1), 110);
+    assert!(!conditions.has_lapsed(stunned_until - 1));
+    conditions.expire(stunned_until);
+    assert!(!conditions.has(Condition::Stunned));
+    assert!(conditions.has(Condition::Blind));
+}
@@ -1,6 +1,5 @@
 use crate::typical::*;
 
-use crate::creature::Stance;
 // Locus
 //
 
@@ -10,7 +9,6 @@ pub(crate) struct Locus {
     pub(crate) velocity: Vec3,
     pub(crate) direction: Dir,
     pub(crate) facing: Dir,
-    pub(crate) stance: Stance,
     pub(crate) pace: Pace,
     pub(crate) weight: f64,
 }
@@ -22,7 +20,6 @@ impl Default for Locus {
             velocity: Vec3::ZERO,
             direction: Dir::North,
             facing: Dir::North,
-            stance: Stance::Standing,
             pace: Pace::default(),
             weight: 80.0,
         }
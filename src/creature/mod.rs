@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+use crate::action::intent::Intents;
 use crate::typical::*;
 
 pub(crate) use anatomy::{APSymmetry, Gender, Side};
@@ -6,9 +7,12 @@ pub(crate) use anatomy::{APSymmetry, Gender, Side};
 pub(crate) mod anatomy;
 pub(crate) mod attributes;
 pub(crate) mod character;
+pub(crate) mod check;
 pub(crate) mod condition;
 pub(crate) mod locus;
 pub(crate) mod pace;
+pub(crate) mod stance;
+pub(crate) mod vision;
 
 pub(crate) use character::*;
 pub(crate) use pace::*;
@@ -16,6 +20,8 @@ pub(crate) use pace::*;
 pub(crate) use attributes::*;
 pub(crate) use condition::*;
 pub(crate) use locus::*;
+pub(crate) use stance::*;
+pub(crate) use vision::*;
 
 #[derive(Bundle, Debug)]
 pub(crate) struct CreatureBundle {
@@ -33,6 +39,9 @@ pub(crate) struct CreatureBundle {
     pub stance: Stance,
     pub approach: Approach,
     pub conditions: ConditionList,
+    pub stamina: Stamina,
+    pub vision: Vision,
+    pub intents: Intents,
     pub locus: Locus,
     pub spatial: SpatialBundle,
 }
@@ -60,10 +69,12 @@ impl CreatureBundle {
 
 impl Default for CreatureBundle {
     fn default() -> Self {
+        let attributes = Attributes::new();
         Self {
             actor: Actor::default(),
             creature: Creature::default(),
-            attributes: Attributes::new(),
+            stamina: Stamina::new(&attributes.secondary),
+            attributes,
             species: Species::Dwarf,
             gender: Gender::Male,
             needs: NeedList::default(),
@@ -75,12 +86,31 @@ impl Default for CreatureBundle {
             stance: Stance::default(),
             approach: Approach::default(),
             conditions: ConditionList::default(),
+            vision: Vision::default(),
+            intents: Intents::default(),
             locus: Locus::default(),
             spatial: SpatialBundle::default(),
         }
     }
 }
 
+// no factions yet: it's the player against everyone else
+pub(crate) fn is_hostile(players: &Query<(), With<Player>>, a: Entity, b: Entity) -> bool {
+    players.contains(a) != players.contains(b)
+}
+
+// who goes first when things happen at once: quicker reflexes win, then
+// agility; ties go to whoever's been around longest
+pub(crate) type Initiative = (u8, u8, std::cmp::Reverse<u32>);
+
+pub(crate) fn initiative(entity: Entity, attributes: &Attributes) -> Initiative {
+    (
+        attributes.secondary.reflexes,
+        attributes.primary.agility,
+        std::cmp::Reverse(entity.index()),
+    )
+}
+
 // CREATURE
 //
 
@@ -145,24 +175,63 @@ pub(crate) struct NeedList {
     pub safety: u8,
 }
 
-// should be a state machine perhaps?
-#[derive(Component, Debug, Clone, Copy, Default, Eq, PartialEq)]
-pub(crate) enum Stance {
-    Dynamic,
-    #[default]
-    Standing,
-    Crouching,
-    Kneeling,
-    Prone,
-    // Grappling,
-    // Flatfooted,
-    // Unbalanced,
-    // Falling,
-    // Unconscious,
-    // Climbing,
-    // Walking,
-    // Running,
-    // Jumping,
+// game time it takes for a need to grow by a point
+const HUNGER_INTERVAL: Dur = Dur::hours(1);
+const THIRST_INTERVAL: Dur = Dur::minutes(30);
+const SLEEP_INTERVAL: Dur = Dur::hours(1);
+// and for rest to ease tiredness by one
+const REST_INTERVAL: Dur = Dur::minutes(10);
+
+impl NeedList {
+    // needs grow with every interval passed, so short waits add up; staying
+    // up through the night is twice as tiring
+    pub(crate) fn pass_time(&mut self, from_tick: u32, to_tick: u32, resting: bool) {
+        let passed = |interval: Dur| {
+            let interval = interval.as_u32();
+            u8::try_from(to_tick / interval - from_tick / interval).unwrap_or(u8::MAX)
+        };
+
+        self.hunger = self.hunger.saturating_add(passed(HUNGER_INTERVAL));
+        self.thirst = self.thirst.saturating_add(passed(THIRST_INTERVAL));
+        let hour = SLEEP_INTERVAL.as_u32();
+        let night_hours = (from_tick / hour + 1..=to_tick / hour)
+            .filter(|h| Clock::new(TickCount(h * hour)).is_night())
+            .count();
+
+        self.sleep = if resting {
+            self.sleep.saturating_sub(passed(REST_INTERVAL))
+        } else {
+            self.sleep
+                .saturating_add(passed(SLEEP_INTERVAL))
+                .saturating_add(u8::try_from(night_hours).unwrap_or(u8::MAX))
+        };
+    }
+}
+
+// spent on exertion, and recovered by taking it easy
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Stamina {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Stamina {
+    pub(crate) fn new(secondary: &Secondary) -> Self {
+        let max = secondary.stamina as u32 * 10;
+        Stamina { current: max, max }
+    }
+
+    pub(crate) fn can_spend(&self, amount: u32) -> bool {
+        self.current >= amount
+    }
+
+    pub(crate) fn spend(&mut self, amount: u32) {
+        self.current = self.current.saturating_sub(amount);
+    }
+
+    pub(crate) fn recover(&mut self, amount: u32) {
+        self.current = (self.current + amount).min(self.max);
+    }
 }
 
 // #[derive(Component, Debug, Clone, Default)]
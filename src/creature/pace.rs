@@ -44,6 +44,8 @@ pub(crate) struct Pace {
     id: PaceId,
     name: &'static str,
     ticks_per_cell: u32,
+    // to get up to it from a standstill
+    strides_to_full_speed: u8,
     sneak_modifier: i16,
 }
 
@@ -54,63 +56,91 @@ impl Default for Pace {
 }
 
 impl Pace {
-    fn get(id: PaceId) -> Pace {
+    pub(crate) fn get(id: PaceId) -> Pace {
         PACE[id as usize]
     }
+
+    pub(crate) fn id(&self) -> PaceId {
+        self.id
+    }
+
+    pub(crate) fn ticks_per_cell(&self) -> u32 {
+        self.ticks_per_cell
+    }
+
+    pub(crate) fn strides_to_full_speed(&self) -> u8 {
+        self.strides_to_full_speed
+    }
 }
 
-const PACE: [Pace; 9] = [
+const PACE: [Pace; 10] = [
     Pace {
         id: Immobile,
         ticks_per_cell: u32::MAX,
+        strides_to_full_speed: 1,
         sneak_modifier: 12,
         name: "immobile",
     },
     Pace {
         id: Interminable,
         ticks_per_cell: 80,
+        strides_to_full_speed: 1,
         sneak_modifier: 6,
         name: "interminable",
     },
     Pace {
         id: Slow,
         ticks_per_cell: 40,
+        strides_to_full_speed: 1,
         sneak_modifier: 4,
         name: "slow",
     },
     Pace {
         id: Cautious,
         ticks_per_cell: 20,
+        strides_to_full_speed: 1,
         sneak_modifier: 2,
         name: "cautious",
     },
     Pace {
         id: Deliberate,
         ticks_per_cell: 10,
+        strides_to_full_speed: 1,
         sneak_modifier: 1,
         name: "deliberate",
     },
     Pace {
         id: Relaxed,
         ticks_per_cell: 8,
+        strides_to_full_speed: 2,
         sneak_modifier: 0,
         name: "relaxed",
     },
     Pace {
         id: Brisk,
         ticks_per_cell: 6,
+        strides_to_full_speed: 2,
         sneak_modifier: -2,
         name: "brisk",
     },
     Pace {
         id: Rapid,
         ticks_per_cell: 3,
+        strides_to_full_speed: 3,
         sneak_modifier: -4,
         name: "rapid",
     },
+    Pace {
+        id: Running,
+        ticks_per_cell: 2,
+        strides_to_full_speed: 4,
+        sneak_modifier: -6,
+        name: "running",
+    },
     Pace {
         id: Reckless,
         ticks_per_cell: 1, // no resolution to go faster without double-moves
+        strides_to_full_speed: 5,
         sneak_modifier: -8,
         name: "reckless",
     },
@@ -120,4 +150,6 @@ const PACE: [Pace; 9] = [
 fn pace_index() {
     assert_eq!(Pace::get(PaceId::Cautious).id, PaceId::Cautious);
     assert_eq!(PACE[PaceId::Cautious as usize].id, PaceId::Cautious);
+    assert_eq!(Pace::get(PaceId::Running).id, PaceId::Running);
+    assert_eq!(Pace::get(PaceId::Reckless).id, PaceId::Reckless);
 }
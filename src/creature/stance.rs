@@ -0,0 +1,158 @@
+use crate::typical::*;
+
+// Stance
+//
+// how a creature holds itself. Lower is slower, harder to spot and harder to
+// shoot - especially with something low in the way - but worse at fending
+// off a blade. Dynamic is a fighter's stance: on the balls of the feet, ready
+// to move, and impossible to miss.
+//
+// changing stance is an action of its own; getting down is quicker than
+// getting back up.
+
+// should be a state machine perhaps?
+#[derive(Component, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub(crate) enum Stance {
+    Dynamic,
+    #[default]
+    Standing,
+    Crouching,
+    Kneeling,
+    Prone,
+    // Grappling,
+    // Flatfooted,
+    // Unbalanced,
+    // Falling,
+    // Unconscious,
+    // Climbing,
+    // Walking,
+    // Running,
+    // Jumping,
+}
+
+// ticks per level to get down, and to get back up
+const DOWN_TICKS: u32 = 2;
+const UP_TICKS: u32 = 4;
+// to settle into, or out of, a fighting stance
+const DYNAMIC_TICKS: u32 = 3;
+
+// how much a low wall, table or barrel between shooter and target helps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Cover {
+    Exposed,
+    Partial,
+    // nothing to shoot at
+    Full,
+}
+
+impl Stance {
+    // how far off the ground: getting up is climbing back through these
+    fn level(&self) -> u32 {
+        match self {
+            Stance::Dynamic | Stance::Standing => 3,
+            Stance::Crouching => 2,
+            Stance::Kneeling => 1,
+            Stance::Prone => 0,
+        }
+    }
+
+    // before how quick the creature is about it
+    pub(crate) fn change_ticks(&self, to: Stance) -> u32 {
+        let (from_level, to_level) = (self.level(), to.level());
+        let levels = if to_level < from_level {
+            (from_level - to_level) * DOWN_TICKS
+        } else {
+            (to_level - from_level) * UP_TICKS
+        };
+        let settling = if (*self == Stance::Dynamic) != (to == Stance::Dynamic) {
+            DYNAMIC_TICKS
+        } else {
+            0
+        };
+        (levels + settling).max(1)
+    }
+
+    // multiplies the time to cross a cell: a crouching shuffle, a crawl
+    pub(crate) fn move_factor(&self) -> f32 {
+        match self {
+            Stance::Dynamic => 1.1,
+            Stance::Standing => 1.0,
+            Stance::Crouching => 1.5,
+            Stance::Kneeling => 3.0,
+            Stance::Prone => 4.0,
+        }
+    }
+
+    pub(crate) fn can_run(&self) -> bool {
+        matches!(self, Stance::Dynamic | Stance::Standing)
+    }
+
+    // added to the roll of anyone trying to make out what they're up to
+    pub(crate) fn conspicuity(&self) -> i32 {
+        match self {
+            Stance::Dynamic => 1,
+            Stance::Standing => 0,
+            Stance::Crouching => -2,
+            Stance::Kneeling => -2,
+            Stance::Prone => -4,
+        }
+    }
+
+    // added to defence against blows
+    pub(crate) fn melee_defence(&self) -> i32 {
+        match self {
+            Stance::Dynamic => 2,
+            Stance::Standing => 0,
+            Stance::Crouching => -1,
+            Stance::Kneeling => -2,
+            Stance::Prone => -4,
+        }
+    }
+
+    // added to defence against arrows and the like, before any cover
+    pub(crate) fn missile_defence(&self) -> i32 {
+        match self {
+            Stance::Dynamic => 1,
+            Stance::Standing => 0,
+            Stance::Crouching => 1,
+            Stance::Kneeling => 2,
+            Stance::Prone => 3,
+        }
+    }
+
+    // behind something about waist high
+    pub(crate) fn cover_behind_low_feature(&self) -> Cover {
+        match self {
+            Stance::Dynamic | Stance::Standing => Cover::Exposed,
+            Stance::Crouching => Cover::Partial,
+            Stance::Kneeling | Stance::Prone => Cover::Full,
+        }
+    }
+}
+
+// cover for someone at pos from a shot coming from the given direction: only
+// a feature right alongside them, on the shooter's side, counts
+pub(crate) fn cover(board: &Board, pos: IVec3, from: IVec3, stance: Stance) -> Cover {
+    let toward = (from - pos).truncate().signum().extend(0);
+    if toward == IVec3::ZERO || board.feature_store.get(&(pos + toward)).is_none() {
+        Cover::Exposed
+    } else {
+        stance.cover_behind_low_feature()
+    }
+}
+
+#[test]
+fn test_getting_up_is_slower() {
+    assert_eq!(Stance::Standing.change_ticks(Stance::Prone), 6);
+    assert_eq!(Stance::Prone.change_ticks(Stance::Standing), 12);
+    assert_eq!(
+        Stance::Standing.change_ticks(Stance::Dynamic),
+        DYNAMIC_TICKS
+    );
+    assert!(Stance::Kneeling.change_ticks(Stance::Crouching) > 0);
+
+    // lower is harder to hit from afar, easier up close
+    assert!(Stance::Prone.missile_defence() > Stance::Standing.missile_defence());
+    assert!(Stance::Prone.melee_defence() < Stance::Standing.melee_defence());
+    assert!(Stance::Crouching.cover_behind_low_feature() > Cover::Exposed);
+}
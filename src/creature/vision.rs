@@ -0,0 +1,232 @@
+use crate::board::direction::DIRECTIONS;
+use crate::board::light::{LightMap, FULL_LIGHT};
+use crate::board::shape;
+use crate::typical::*;
+
+use super::{Attributes, Condition, ConditionList};
+
+// Vision
+//
+// what a creature can see is worked out the same way for the player and for AI:
+// from its perception, the light falling on each cell, how well its eyes are
+// acclimated to the dark, and any conditions clouding its sight.
+//
+// cells in the focus arc are seen clearly; those in the peripheral arc only
+// well enough to catch movement.
+
+const BASE_RANGE: f32 = 4.0;
+const FOCUS_ARC_DEGREES: f32 = 120.;
+const PERIPHERAL_ARC_DEGREES: f32 = 200.;
+// close enough to make out a shape in the faintest glimmer
+const ADJACENT_RANGE: f32 = 1.5;
+
+pub(crate) const PERIPHERAL_DETECTION: f32 = 0.35;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Sight {
+    pub(crate) range: f32,          // cells, in full light
+    pub(crate) focus_arc: f32,      // radians
+    pub(crate) peripheral_arc: f32, // radians, including the focus arc
+    pub(crate) acclimation: u8,     // light levels gained in the dark
+}
+
+impl Sight {
+    pub(crate) fn new(
+        attributes: &Attributes,
+        species: &Species,
+        conditions: &ConditionList,
+    ) -> Self {
+        if conditions.has(Condition::Blind) {
+            return Sight::default();
+        }
+
+        let mut sight = Sight {
+            range: BASE_RANGE + attributes.primary.perception as f32,
+            focus_arc: f32::to_radians(FOCUS_ARC_DEGREES),
+            peripheral_arc: f32::to_radians(PERIPHERAL_ARC_DEGREES),
+            acclimation: night_acclimation(species),
+        };
+
+        if conditions.has(Condition::Dizzy) {
+            sight.range *= 0.75;
+            sight.focus_arc *= 0.5;
+        }
+
+        if conditions.has(Condition::Concussed) {
+            sight.range *= 0.5;
+            sight.peripheral_arc = sight.focus_arc;
+        }
+
+        sight
+    }
+
+    // how far away things can be made out at a given light level
+    pub(crate) fn range_in_light(&self, light: u8) -> f32 {
+        let light = light.saturating_add(self.acclimation).min(FULL_LIGHT);
+        self.range * light as f32 / FULL_LIGHT as f32
+    }
+}
+
+pub(crate) fn night_acclimation(species: &Species) -> u8 {
+    match species {
+        Species::Rat => 6,
+        Species::Goblin => 5,
+        Species::Dwarf => 4,
+        Species::Elf => 2,
+        _ => 0,
+    }
+}
+
+#[derive(Component, Debug, Clone, Default)]
+pub(crate) struct Vision {
+    pub(crate) sight: Sight,
+    pub(crate) focus: HashSet<IVec3>,
+    pub(crate) peripheral: HashSet<IVec3>,
+}
+
+impl Vision {
+    pub(crate) fn can_see(&self, pos: &IVec3) -> bool {
+        self.focus.contains(pos) || self.peripheral.contains(pos)
+    }
+
+    // chance multiplier for noticing something in a given cell
+    pub(crate) fn detection(&self, pos: &IVec3) -> f32 {
+        if self.focus.contains(pos) {
+            1.0
+        } else if self.peripheral.contains(pos) {
+            PERIPHERAL_DETECTION
+        } else {
+            0.0
+        }
+    }
+
+    pub(crate) fn update(&mut self, sight: Sight, locus: &Locus, board: &Board, light: &LightMap) {
+        self.sight = sight;
+        self.focus.clear();
+        self.peripheral.clear();
+
+        if sight.range <= 0.0 {
+            return;
+        }
+
+        let origin = locus.position;
+        let facing = locus.facing.to_radians();
+
+        for pos in shape::cone(
+            board.bounds(),
+            origin,
+            facing,
+            sight.peripheral_arc,
+            sight.range,
+        ) {
+            let distance = shape::distance_squared(origin, pos).sqrt();
+            if distance > ADJACENT_RANGE && distance > sight.range_in_light(light.level_at(&pos)) {
+                continue;
+            }
+            if !board.has_line_of_sight(origin, pos) {
+                continue;
+            }
+
+            if pos == origin
+                || shape::within_arc(shape::bearing(origin, pos), facing, sight.focus_arc)
+            {
+                self.focus.insert(pos);
+            } else {
+                self.peripheral.insert(pos);
+            }
+        }
+    }
+}
+
+// every cell a creature has laid eyes on
+#[derive(Component, Debug, Clone, Default)]
+pub(crate) struct Explored {
+    pub(crate) cells: HashSet<IVec3>,
+}
+
+impl Explored {
+    pub(crate) fn contains(&self, pos: &IVec3) -> bool {
+        self.cells.contains(pos)
+    }
+
+    // explored, passable cells on the edge of the unknown - nearest first
+    pub(crate) fn frontier<'a>(
+        &'a self,
+        board: &'a Board,
+        from: IVec3,
+    ) -> impl Iterator<Item = IVec3> + 'a {
+        shape::flood_fill(board.bounds(), from, move |pos: &IVec3| {
+            self.contains(pos) && board.is_unoccupied(pos)
+        })
+        .filter(move |pos| {
+            DIRECTIONS.iter().any(|dir| {
+                let n = *pos + dir.offset();
+                board.bounds().contains(&n) && !self.contains(&n)
+            })
+        })
+    }
+}
+
+// who else can be seen, other than the observer
+pub(crate) fn visible_creatures(
+    vision: &Vision,
+    board: &Board,
+    observer: Entity,
+) -> HashSet<Entity> {
+    board
+        .creature_store
+        .iter()
+        .filter(|(pos, entity)| **entity != observer && vision.can_see(pos))
+        .map(|(_, entity)| *entity)
+        .collect()
+}
+
+pub(crate) fn update_vision(
+    board: Res<Board>,
+    light: Res<LightMap>,
+    mut query: Query<(&Locus, &Attributes, &Species, &ConditionList, &mut Vision)>,
+) {
+    for (locus, attributes, species, conditions, mut vision) in query.iter_mut() {
+        let sight = Sight::new(attributes, species, conditions);
+        vision.update(sight, locus, &board, &light);
+    }
+}
+
+pub(crate) fn remember_explored(mut query: Query<(&Vision, &mut Explored)>) {
+    for (vision, mut explored) in query.iter_mut() {
+        explored
+            .cells
+            .extend(vision.focus.iter().chain(vision.peripheral.iter()));
+    }
+}
+
+#[test]
+fn test_sight_from_conditions() {
+    let attributes = Attributes::new();
+    let mut conditions = ConditionList::default();
+    let clear = Sight::new(&attributes, &Species::Dwarf, &conditions);
+
+    conditions.add(Condition::Concussed);
+    let concussed = Sight::new(&attributes, &Species::Dwarf, &conditions);
+    assert!(concussed.range < clear.range);
+    assert_eq!(concussed.peripheral_arc, concussed.focus_arc);
+
+    conditions.add(Condition::Blind);
+    let blind = Sight::new(&attributes, &Species::Dwarf, &conditions);
+    assert_eq!(blind.range, 0.0);
+}
+
+#[test]
+fn test_dwarves_see_further_in_the_dark() {
+    let attributes = Attributes::new();
+    let conditions = ConditionList::default();
+    let dwarf = Sight::new(&attributes, &Species::Dwarf, &conditions);
+    let human = Sight::new(&attributes, &Species::Human, &conditions);
+
+    assert_eq!(human.range_in_light(0), 0.0);
+    assert!(dwarf.range_in_light(0) > 0.0);
+    assert_eq!(
+        dwarf.range_in_light(FULL_LIGHT),
+        human.range_in_light(FULL_LIGHT)
+    );
+}
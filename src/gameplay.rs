@@ -0,0 +1,230 @@
+use crate::action::kind::ActionKindSet;
+use crate::input::PlayerInputState;
+use crate::player::SpawnPlayerEvent;
+use crate::typical::*;
+use crate::{action, board, creature, graphics, input, inventory, journal, scheduler, time};
+
+// Gameplay
+//
+// everything which makes the game go - resources, states, events and the
+// action loop - without anything which draws it, so the same rules run in the
+// window and in a headless simulation.
+
+// System sets and such
+#[derive(Ord, SystemSet, Debug, Hash, PartialEq, Eq, Clone, PartialOrd)]
+pub(crate) enum ActionSet {
+    // Plan:
+    Assign,
+    Validate,
+    // Run:
+    Tick,
+    Apply,
+    // AwaitAnim:
+    Animate,
+}
+
+// once the board and creatures are in place: see, light and plan for the first time
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub(crate) struct BeginPlay;
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+struct CustomFlush;
+
+pub(crate) struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // PLUGINS
+            .add_plugins(time::TimePlugin)
+            .add_plugins(journal::JournalPlugin)
+            .add_plugins(scheduler::SchedulerPlugin)
+            // RESOURCES
+            .init_resource::<Board>()
+            .init_resource::<board::light::LightMap>()
+            .init_resource::<board::flow_field::FlowFields>()
+            .init_resource::<action::time_advance::TimeAdvance>()
+            .init_resource::<action::timeline::Timeline>()
+            .init_resource::<creature::condition::ConditionDurations>()
+            // STATE
+            .add_state::<ActionSystemState>()
+            .add_state::<PlayerInputState>()
+            // EVENTS
+            .add_event::<SpawnPlayerEvent>()
+            .add_event::<TickEvent>()
+            .add_event::<PlayerInputRequestEvent>()
+            .add_event::<ActionInvalidatedEvent>()
+            .add_event::<ActionValidatedEvent>()
+            .add_event::<ActionPlanRequestEvent>()
+            .add_event::<ActionCompleteEvent>()
+            .add_event::<ActionAddedEvent>()
+            .add_event::<ActionStartedEvent>()
+            .add_event::<ActionAbortedEvent>()
+            .add_event::<ActionInterruptedEvent>()
+            .add_event::<StillWaitForAnimEvent>()
+            .add_event::<SpawnGoblinEvent>()
+            .add_event::<action::command::CommandEvent>()
+            .add_event::<input::CellClickedEvent>()
+            .add_event::<input::CreatureClickedEvent>()
+            .add_event::<board::TerrainChangedEvent>()
+            //
+            // SYSTEMS
+            //
+            // Startup
+            //
+            .add_systems(
+                Startup,
+                (
+                    board::announce_terrain_changes,
+                    board::light::update_light_map,
+                    creature::vision::update_vision,
+                    creature::vision::remember_explored,
+                    board::flow_field::update_flow_fields,
+                    action::bootstrap,
+                )
+                    .chain()
+                    .in_set(BeginPlay),
+            )
+            //
+            // Actions
+            //
+            .configure_sets(
+                PreUpdate,
+                (
+                    ActionSet::Assign,
+                    ActionSet::Validate,
+                    ActionSet::Tick,
+                    ActionSet::Apply,
+                    ActionSet::Animate,
+                )
+                    .chain(),
+            )
+            //
+            .configure_sets(
+                PreUpdate,
+                (
+                    ActionKindSet::Validate
+                        .in_set(ActionSet::Validate)
+                        .before(action::handle_action_invalid)
+                        .run_if(in_state(ActionSystemState::Plan)),
+                    ActionKindSet::Effect
+                        .in_set(ActionSet::Apply)
+                        .before(action::apply_completed_actions)
+                        .run_if(on_event::<ActionCompleteEvent>()),
+                    ActionKindSet::React
+                        .in_set(ActionSet::Apply)
+                        .after(creature::vision::update_vision)
+                        .before(action::set_state_await_anim)
+                        .run_if(on_event::<ActionCompleteEvent>()),
+                ),
+            )
+            .add_plugins(action::kinds::ActionKindsPlugin)
+            .add_systems(
+                OnEnter(ActionSystemState::Plan),
+                (
+                    action::timeline::sync_timeline,
+                    action::plan_init_check_or_tick,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    // catch up with anything changed since last frame
+                    action::timeline::sync_timeline.before(ActionSet::Assign),
+                    (
+                        (
+                            action::timeline::sync_timeline,
+                            action::plan_init_check_or_tick,
+                        )
+                            .chain()
+                            .run_if(
+                                on_event::<ActionAddedEvent>()
+                                    .or_else(on_event::<ActionValidatedEvent>()),
+                            ),
+                        input::keybindings.run_if(in_state(PlayerInputState::Listen)),
+                        input::pointer_commands.run_if(in_state(PlayerInputState::Listen)),
+                        action::auto_move::continue_auto_move,
+                        action::command::dispatch_queued_commands,
+                        action::plan_agent_actions.run_if(on_event::<ActionPlanRequestEvent>()),
+                        action::command::issue_commands
+                            .run_if(on_event::<action::command::CommandEvent>()),
+                        apply_deferred.run_if(on_event::<ActionAddedEvent>()),
+                    )
+                        .chain()
+                        .in_set(ActionSet::Assign)
+                        .run_if(in_state(ActionSystemState::Plan)),
+                    (
+                        // each action kind's validation runs before this
+                        action::handle_action_invalid.run_if(on_event::<ActionInvalidatedEvent>()),
+                        apply_deferred,
+                    )
+                        .chain()
+                        .in_set(ActionSet::Validate)
+                        .run_if(in_state(ActionSystemState::Plan)),
+                    (
+                        action::set_state_run,
+                        action::timeline::sync_timeline,
+                        action::time_advance::advance_clock,
+                        scheduler::drain_scheduler,
+                        creature::condition::expire_conditions,
+                        action::tick_actions,
+                        apply_deferred,
+                        action::intent::read_intents,
+                        action::kinds::wait::watch_waits,
+                        action::kinds::hold::arm_holds,
+                        apply_deferred,
+                        action::kinds::wait::disturb_waits
+                            .run_if(any_with_component::<action::kinds::wait::Watch>()),
+                        apply_deferred,
+                        action::timeline::sync_timeline,
+                        action::plan_init_check_or_tick, // proceed to next tick?
+                        action::time_advance::return_to_plan_in_real_time,
+                    )
+                        .chain()
+                        .in_set(ActionSet::Tick)
+                        .run_if(on_event::<TickEvent>()),
+                    (
+                        // each action kind's effects run before this
+                        action::apply_completed_actions,
+                        apply_deferred,
+                        // walls and features changed by the effects
+                        board::announce_terrain_changes,
+                        inventory::pick_up_items,
+                        board::light::update_light_map,
+                        creature::vision::update_vision,
+                        creature::vision::remember_explored,
+                        board::flow_field::update_flow_fields,
+                        action::auto_move::interrupt_auto_move,
+                        action::command::interrupt_queued_commands,
+                        action::set_state_await_anim,
+                        apply_deferred,
+                    )
+                        .chain()
+                        .in_set(ActionSet::Apply)
+                        .run_if(on_event::<ActionCompleteEvent>()),
+                ),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    action::auto_move::stop_auto_move.run_if(on_event::<ActionAbortedEvent>()),
+                    action::time_advance::auto_pause,
+                )
+                    .after(ActionSet::Apply),
+            )
+            .add_systems(
+                Update,
+                (
+                    graphics::move_anim::lerp_vec3_translation,
+                    // keep-alive or return to set player input
+                    (action::set_state_plan, apply_deferred.in_set(CustomFlush))
+                        .chain()
+                        .run_if(not(on_event::<StillWaitForAnimEvent>())),
+                )
+                    .chain()
+                    .in_set(ActionSet::Animate)
+                    .run_if(in_state(ActionSystemState::AwaitAnim)),
+            );
+    }
+}
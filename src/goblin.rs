@@ -1,5 +1,7 @@
 #![allow(dead_code)]
+use crate::input::CreatureClickedEvent;
 use crate::typical::{graphics::GoblinSpritesheet, *};
+use bevy_mod_picking::prelude::*;
 
 #[derive(Event, Debug)]
 pub(crate) struct SpawnGoblinEvent(pub IVec3);
@@ -22,14 +24,21 @@ pub(crate) fn spawn_goblins(
     commands.entity(e).with_children(|on_board| {
         for SpawnGoblinEvent(position) in ev_gobs.read() {
             let goblin_id = on_board
-                .spawn(goblin_bundle(position))
+                .spawn((
+                    goblin_bundle(position),
+                    // clicks on the sprite bubble up to here
+                    On::<Pointer<Click>>::send_event::<CreatureClickedEvent>(),
+                ))
                 .with_children(|gobbo| {
-                    gobbo.spawn((SpriteSheetBundle {
-                        texture_atlas: sprite.atlas_handle.clone(),
-                        sprite: TextureAtlasSprite::new(0),
-                        transform: Transform::from_translation(Vec3::splat(0.)), // (position.as_vec3()),
-                        ..default()
-                    },));
+                    gobbo.spawn((
+                        SpriteSheetBundle {
+                            texture_atlas: sprite.atlas_handle.clone(),
+                            sprite: TextureAtlasSprite::new(0),
+                            transform: Transform::from_translation(Vec3::splat(0.)), // (position.as_vec3()),
+                            ..default()
+                        },
+                        PickableBundle::default(),
+                    ));
                 })
                 .id();
             board.creature_store.insert(goblin_id, *position);
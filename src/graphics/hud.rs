@@ -0,0 +1,121 @@
+use crate::action::command::CommandQueue;
+use crate::action::intent::Intents;
+use crate::typical::*;
+
+// what the player has lined up to do next
+
+#[derive(Component, Debug)]
+pub(crate) struct CommandQueueText;
+
+pub(crate) fn spawn_command_queue_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..default()
+        }),
+        CommandQueueText,
+    ));
+}
+
+pub(crate) fn update_command_queue_text(
+    queue: Query<&CommandQueue, (With<Player>, Changed<CommandQueue>)>,
+    mut text: Query<&mut Text, With<CommandQueueText>>,
+) {
+    let (Ok(queue), Ok(mut text)) = (queue.get_single(), text.get_single_mut()) else {
+        return;
+    };
+    text.sections[0].value = match queue.describe() {
+        pending if pending.is_empty() => String::new(),
+        pending => format!("queued: {}", pending),
+    };
+}
+
+// the date and time of day
+
+#[derive(Component, Debug)]
+pub(crate) struct ClockText;
+
+pub(crate) fn spawn_clock_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            right: Val::Px(8.0),
+            ..default()
+        }),
+        ClockText,
+    ));
+}
+
+pub(crate) fn update_clock_text(clock: Res<Clock>, mut text: Query<&mut Text, With<ClockText>>) {
+    if !clock.is_changed() {
+        return;
+    }
+    let Ok(mut text) = text.get_single_mut() else {
+        return;
+    };
+    text.sections[0].value = format!("{} ({:?})", clock, clock.day_phase());
+}
+
+// what the player makes of everyone else in sight
+
+#[derive(Component, Debug)]
+pub(crate) struct IntentsText;
+
+pub(crate) fn spawn_intents_text(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            ..default()
+        }),
+        IntentsText,
+    ));
+}
+
+pub(crate) fn update_intents_text(
+    player: Query<(Entity, &Intents), (With<Player>, Changed<Intents>)>,
+    species: Query<&Species>,
+    mut text: Query<&mut Text, With<IntentsText>>,
+) {
+    let (Ok((player, intents)), Ok(mut text)) = (player.get_single(), text.get_single_mut()) else {
+        return;
+    };
+    let mut lines: Vec<String> = intents
+        .known
+        .iter()
+        .map(|(actor, intent)| match species.get(*actor) {
+            Ok(species) => format!("{:?}: {}", species, intent.describe(player)),
+            Err(_) => intent.describe(player),
+        })
+        .collect();
+    lines.sort();
+    text.sections[0].value = lines.join("\n");
+}
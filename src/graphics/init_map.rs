@@ -3,12 +3,15 @@ use crate::typical::*;
 // use crate::Board;
 use bevy::pbr::OpaqueRendererMethod;
 use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::input::CellClickedEvent;
 
 // slightly larger than 1.0 so the overlap prevents bleed through
 const VOXEL_CUBE_SIZE: f32 = 1.0;
 // const VOXEL_CUBE_MARGIN: f32 = 0.08;
 
-#[derive(Resource, Debug)]
+#[derive(Resource, Debug, Default)]
 pub(crate) struct CellCubeEntityMapper(HashMap<Entity, Entity>, HashMap<Entity, Entity>);
 
 // in case we need it for terrain destruction, etc
@@ -21,6 +24,10 @@ impl CellCubeEntityMapper {
         self.0.insert(cell_entity, cube_entity);
         self.1.insert(cube_entity, cell_entity);
     }
+
+    pub(crate) fn cell_for(&self, cube_entity: &Entity) -> Option<Entity> {
+        self.1.get(cube_entity).copied()
+    }
 }
 
 pub(crate) fn spawn_voxel_map(
@@ -81,14 +88,18 @@ pub(crate) fn spawn_voxel_map(
             for (ivec, cell_entity) in board.cell_store.iter() {
                 let [x, y, z] = ivec.to_array();
 
-                // floors
+                // floors - click to travel; highlighted on hover
                 let cube_entity = ch
-                    .spawn((PbrBundle {
-                        mesh: shape.clone(),
-                        material: floor_material.clone(),
-                        transform: Transform::from_xyz(x as f32, y as f32, z as f32 - 1.0),
-                        ..default()
-                    },))
+                    .spawn((
+                        PbrBundle {
+                            mesh: shape.clone(),
+                            material: floor_material.clone(),
+                            transform: Transform::from_xyz(x as f32, y as f32, z as f32 - 1.0),
+                            ..default()
+                        },
+                        PickableBundle::default(),
+                        On::<Pointer<Click>>::send_event::<CellClickedEvent>(),
+                    ))
                     .id();
                 mapper.insert(*cell_entity, cube_entity);
             }
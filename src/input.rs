@@ -1,4 +1,12 @@
-use crate::action::{Action, ActionDetail, ActionStatus, Actor, ActorAction, MovementActionDetail};
+use bevy_mod_picking::prelude::*;
+
+use crate::action::auto_move::{AutoMove, AutoMoveMode};
+use crate::action::command::{cancel_commands, Command, CommandQueue};
+use crate::action::kinds::hold::{Response, Trigger};
+use crate::action::time_advance::TimeAdvance;
+use crate::action::{Actor, ActorAction};
+use crate::creature::visible_creatures;
+use crate::graphics::CellCubeEntityMapper;
 
 use crate::typical::*;
 
@@ -9,37 +17,213 @@ pub(crate) enum PlayerInputState {
     Inactive = 0,
 }
 
+// arrows, the keys around them, or the numpad
+const DIRECTION_KEYS: [(KeyCode, Dir); 16] = [
+    (KeyCode::Up, Dir::North),
+    (KeyCode::Down, Dir::South),
+    (KeyCode::Left, Dir::West),
+    (KeyCode::Right, Dir::East),
+    (KeyCode::Home, Dir::NorthWest),
+    (KeyCode::PageUp, Dir::NorthEast),
+    (KeyCode::End, Dir::SouthWest),
+    (KeyCode::PageDown, Dir::SouthEast),
+    (KeyCode::Numpad8, Dir::North),
+    (KeyCode::Numpad2, Dir::South),
+    (KeyCode::Numpad4, Dir::West),
+    (KeyCode::Numpad6, Dir::East),
+    (KeyCode::Numpad7, Dir::NorthWest),
+    (KeyCode::Numpad9, Dir::NorthEast),
+    (KeyCode::Numpad1, Dir::SouthWest),
+    (KeyCode::Numpad3, Dir::SouthEast),
+];
+
+const DIGIT_KEYS: [KeyCode; 10] = [
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+const CANCEL_KEY: KeyCode = KeyCode::Back;
+const WAIT: Dur = Dur::seconds(1);
+
+// moving issues a command, queued up behind any others; a number typed first repeats it
 pub(crate) fn keybindings(
-    mut get_player: Query<(Entity, &Player, &mut Actor), Without<ActorAction>>,
-    mut ev_added: EventWriter<ActionAddedEvent>,
+    mut get_player: Query<
+        (
+            Entity,
+            &mut Actor,
+            &mut CommandQueue,
+            &Vision,
+            Option<&ActorAction>,
+        ),
+        With<Player>,
+    >,
     mut commands: Commands,
     keys: Res<Input<KeyCode>>,
+    board: Res<Board>,
 ) {
     let shifted: bool = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let ctrl: bool = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let alt: bool = keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+
+    let direction = DIRECTION_KEYS
+        .iter()
+        .find(|(key, _)| keys.just_pressed(*key))
+        .map(|(_, dir)| *dir);
+
+    let Ok((entity, mut actor, mut queue, vision, action)) = get_player.get_single_mut() else {
+        return;
+    };
+
+    if keys.just_pressed(CANCEL_KEY) {
+        info!("cancelled: {}", queue.describe());
+        cancel_commands(&mut commands, entity, &mut queue, &mut actor);
+        return;
+    }
+
+    if let Some(digit) = DIGIT_KEYS.iter().position(|key| keys.just_pressed(*key)) {
+        queue.push_digit(digit as u32);
+        return;
+    }
+
+    // auto-movement takes over entirely, so only start it with nothing else to do
+    let idle = action.is_none() && actor.queue.is_empty() && queue.is_empty();
+
+    match direction {
+        // shift + direction: run until something interesting happens
+        Some(direction) if shifted => {
+            if idle {
+                let seen = visible_creatures(vision, &board, entity);
+                commands
+                    .entity(entity)
+                    .insert(AutoMove::new(AutoMoveMode::Run(direction), seen));
+            }
+        }
+        // alt + direction: turn to face it, without moving
+        Some(direction) if alt => queue.push(Command::Turn(direction)),
+        // ctrl + direction: run a step
+        Some(direction) if ctrl => queue.push(Command::Run(direction)),
+        Some(direction) => queue.push(Command::Walk(direction)),
+        // wait a second, or as many ticks as typed in first
+        None if keys.just_pressed(KeyCode::W) => {
+            let duration = queue.count.take().map_or(WAIT, Dur::ticks);
+            queue.push(Command::Wait(duration));
+        }
+        None if keys.just_pressed(KeyCode::Z) => queue.push(Command::Rest),
+        // keep watch until something happens
+        None if keys.just_pressed(KeyCode::X) => queue.push(Command::Watch),
+        // brace for anyone stepping up, and strike first
+        None if keys.just_pressed(KeyCode::R) => {
+            queue.push(Command::Hold(Trigger::EnemyInReach, Response::Strike))
+        }
+        None if keys.just_pressed(KeyCode::C) => queue.push(Command::Stance(Stance::Crouching)),
+        None if keys.just_pressed(KeyCode::K) => queue.push(Command::Stance(Stance::Kneeling)),
+        None if keys.just_pressed(KeyCode::P) => queue.push(Command::Stance(Stance::Prone)),
+        None if keys.just_pressed(KeyCode::U) => queue.push(Command::Stance(Stance::Standing)),
+        // on guard
+        None if keys.just_pressed(KeyCode::B) => queue.push(Command::Stance(Stance::Dynamic)),
+        None if keys.just_pressed(KeyCode::O) => {
+            if idle {
+                let seen = visible_creatures(vision, &board, entity);
+                commands
+                    .entity(entity)
+                    .insert(AutoMove::new(AutoMoveMode::Explore, seen));
+            }
+        }
+        None => {}
+    }
+}
+
+// space pauses real time; T changes how time moves on
+pub(crate) fn time_controls(
+    keys: Res<Input<KeyCode>>,
+    mut advance: ResMut<TimeAdvance>,
+    mut ev_tick: EventWriter<TickEvent>,
+) {
+    if keys.just_pressed(KeyCode::T) {
+        advance.next_mode();
+        info!("time advances: {:?}", advance.mode);
+    } else if keys.just_pressed(KeyCode::Space) && advance.real_time() {
+        advance.toggle_pause();
+        info!("paused: {}", advance.paused);
+        if !advance.paused {
+            // get things moving again
+            ev_tick.send(TickEvent);
+        }
+    }
+}
+
+// Pointer input
+//
+// clicks are turned into commands, so they go through the same queue,
+// validation and replanning as anything else the player asks for.
+
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct CellClickedEvent {
+    pub(crate) cube: Entity,
+    pub(crate) button: PointerButton,
+}
+
+impl From<ListenerInput<Pointer<Click>>> for CellClickedEvent {
+    fn from(event: ListenerInput<Pointer<Click>>) -> Self {
+        CellClickedEvent {
+            cube: event.target,
+            button: event.button,
+        }
+    }
+}
 
-    let direction = if keys.just_pressed(KeyCode::Up) {
-        Some(if shifted { Dir::NorthWest } else { Dir::North })
-    } else if keys.just_pressed(KeyCode::Down) {
-        Some(if shifted { Dir::SouthEast } else { Dir::South })
-    } else if keys.just_pressed(KeyCode::Left) {
-        Some(if shifted { Dir::SouthWest } else { Dir::West })
-    } else if keys.just_pressed(KeyCode::Right) {
-        Some(if shifted { Dir::NorthEast } else { Dir::East })
-    } else {
-        None
+#[derive(Event, Debug, Clone, Copy)]
+pub(crate) struct CreatureClickedEvent {
+    pub(crate) creature: Entity,
+    pub(crate) button: PointerButton,
+}
+
+impl From<ListenerInput<Pointer<Click>>> for CreatureClickedEvent {
+    fn from(event: ListenerInput<Pointer<Click>>) -> Self {
+        CreatureClickedEvent {
+            creature: event.listener(),
+            button: event.button,
+        }
+    }
+}
+
+pub(crate) fn pointer_commands(
+    mut ev_cell: EventReader<CellClickedEvent>,
+    mut ev_creature: EventReader<CreatureClickedEvent>,
+    mut get_player: Query<(Entity, &mut CommandQueue), With<Player>>,
+    cells: Query<&Cell>,
+    mapper: Res<CellCubeEntityMapper>,
+) {
+    let Ok((entity, mut queue)) = get_player.get_single_mut() else {
+        ev_cell.clear();
+        ev_creature.clear();
+        return;
     };
 
-    if let Some(direction) = direction {
-        let (entity, _player, _actor) = get_player.single_mut();
-        let movement = MovementActionDetail::Walk(direction);
-        let action = Action {
-            entity,
-            status: ActionStatus::Idle,
-            detail: ActionDetail::Move(movement),
-            duration: 10,
-        };
-        dbg!("key command:", action);
-        commands.entity(entity).insert(ActorAction(action));
-        ev_added.send(ActionAddedEvent { entity });
+    // a creature standing on a cell takes precedence over the floor beneath it
+    let clicked_creature = ev_creature
+        .read()
+        .filter(|ev| ev.button == PointerButton::Primary && ev.creature != entity)
+        .last()
+        .map(|ev| Command::Attack(ev.creature));
+
+    let clicked_cell = ev_cell
+        .read()
+        .filter(|ev| ev.button == PointerButton::Primary)
+        .filter_map(|ev| mapper.cell_for(&ev.cube))
+        .filter_map(|cell_entity| cells.get(cell_entity).ok())
+        .last()
+        .map(|cell| Command::TravelTo(cell.position));
+
+    if let Some(command) = clicked_creature.or(clicked_cell) {
+        queue.push(command);
     }
 }
@@ -41,6 +41,7 @@ Entity (Player, Anatomy, ..)
 #[derive(Event, Debug, Copy, Clone)]
 pub(crate) struct ItemPickUpEvent {
     pub(crate) owner: Entity,
+    pub(crate) item: Entity,
 }
 
 #[derive(Event, Debug, Copy, Clone)]
@@ -215,6 +216,26 @@ pub(crate) struct Valuable {
     pub(crate) computed_value: u16,
 }
 
+// SYSTEMS
+
+// no picking and choosing yet: whoever ends up standing on something takes it
+pub(crate) fn pick_up_items(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    query: Query<(Entity, &Locus), Changed<Locus>>,
+    mut ev_pickup: EventWriter<ItemPickUpEvent>,
+) {
+    for (owner, locus) in query.iter() {
+        let Some(item) = board.items_store.remove(&locus.position) else {
+            continue;
+        };
+        commands
+            .entity(item)
+            .insert(ItemLocation::Hand(owner, Side::Either));
+        ev_pickup.send(ItemPickUpEvent { owner, item });
+    }
+}
+
 // some containers can only contain certain items
 // eg a quiver -> arrows
 
@@ -307,3 +328,45 @@ struct Consumable {
 //     Free,
 //     Occupied,
 // }
+
+#[test]
+fn test_pick_up_what_you_step_on() {
+    let mut app = App::new();
+    app.init_resource::<Board>()
+        .add_event::<ItemPickUpEvent>()
+        .add_systems(Update, pick_up_items);
+
+    let here = IVec3::new(3, 3, 0);
+    let item = app.world.spawn(()).id();
+    app.world
+        .resource_mut::<Board>()
+        .items_store
+        .set(here + IVec3::X, item);
+    let owner = app
+        .world
+        .spawn(Locus {
+            position: here,
+            ..default()
+        })
+        .id();
+    app.update();
+    assert!(app.world.get::<ItemLocation>(item).is_none());
+
+    app.world.get_mut::<Locus>(owner).unwrap().position += IVec3::X;
+    app.update();
+    assert_eq!(
+        app.world.get::<ItemLocation>(item),
+        Some(&ItemLocation::Hand(owner, Side::Either))
+    );
+    assert!(app
+        .world
+        .resource::<Board>()
+        .items_store
+        .get(&(here + IVec3::X))
+        .is_none());
+    let pickups = app.world.resource::<Events<ItemPickUpEvent>>();
+    assert_eq!(
+        pickups.get_reader().read(pickups).map(|ev| ev.item).next(),
+        Some(item)
+    );
+}
@@ -0,0 +1,339 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::kinds::melee::{AttackEvent, CHARGE_IMPACT};
+use crate::action::kinds::wait::Until;
+use crate::action::{ActionDetail, MovementActionDetail};
+use crate::creature::visible_creatures;
+use crate::inventory::ItemPickUpEvent;
+use crate::typical::*;
+
+// Journal
+//
+// a record of everything that happens in the game, as it happens: who did
+// what to whom, and when, with a sentence saying so. Each entry notes whether
+// the player was in a position to know about it, so the message log only
+// shows what they perceived while a bug report can have the lot.
+//
+// only the most recent entries are kept; a long game would otherwise keep
+// every footstep forever.
+
+const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum EntryKind {
+    ActionStarted,
+    ActionCompleted,
+    ActionAborted,
+    ActionInvalidated,
+    Attack,
+    Pickup,
+    Perception,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    pub(crate) tick: u32,
+    pub(crate) kind: EntryKind,
+    pub(crate) actor: Entity,
+    pub(crate) target: Option<Entity>,
+    pub(crate) position: Option<[i32; 3]>,
+    pub(crate) perceived: bool,
+    pub(crate) text: String,
+}
+
+impl Entry {
+    pub(crate) fn involves(&self, entity: Entity) -> bool {
+        self.actor == entity || self.target == Some(entity)
+    }
+}
+
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Journal {
+    entries: VecDeque<Entry>,
+}
+
+impl Journal {
+    pub(crate) fn record(&mut self, entry: Entry) {
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub(crate) fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    // the message log: only what the player saw, or had done to them
+    pub(crate) fn perceived(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(|e| e.perceived)
+    }
+
+    pub(crate) fn involving(&self, entity: Entity) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(move |e| e.involves(entity))
+    }
+
+    pub(crate) fn of_kind(&self, kind: EntryKind) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(move |e| e.kind == kind)
+    }
+
+    pub(crate) fn since(&self, tick: u32) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(move |e| e.tick >= tick)
+    }
+
+    // for bug reports
+    pub(crate) fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub(crate) fn from_ron(text: &str) -> Result<Journal, ron::error::SpannedError> {
+        ron::from_str(text)
+    }
+}
+
+pub(crate) struct JournalPlugin;
+
+impl Plugin for JournalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Journal>()
+            .add_event::<ItemPickUpEvent>()
+            .add_systems(PostUpdate, write_journal);
+    }
+}
+
+// Sentences
+
+// how the journal refers to someone
+fn name(entity: Entity, player: Option<Entity>, species: &Query<&Species>) -> String {
+    if Some(entity) == player {
+        "you".to_string()
+    } else if let Ok(species) = species.get(entity) {
+        format!("the {:?}", species).to_lowercase()
+    } else {
+        "something".to_string()
+    }
+}
+
+// a verb, and whatever follows it
+fn phrase(detail: &ActionDetail, target: Option<&str>) -> (&'static str, String) {
+    let dir = |d: &Dir| format!("{:?}", d).to_lowercase();
+    let target = target.unwrap_or("something").to_string();
+    match detail {
+        ActionDetail::Move(MovementActionDetail::Turn(d)) => ("turn", dir(d)),
+        ActionDetail::Move(MovementActionDetail::Walk(d)) => ("walk", dir(d)),
+        ActionDetail::Move(MovementActionDetail::Run(d)) => ("run", dir(d)),
+        ActionDetail::Attack(_) => ("attack", target),
+        ActionDetail::Shoot(_) => ("shoot", format!("at {}", target)),
+        ActionDetail::Wait(Until::Rested) => ("rest", String::new()),
+        ActionDetail::Wait(_) => ("wait", String::new()),
+        ActionDetail::Hold(_) => ("hold", "ready".to_string()),
+        ActionDetail::Stance(Stance::Dynamic) => ("brace", String::new()),
+        ActionDetail::Stance(Stance::Standing) => ("stand", "up".to_string()),
+        ActionDetail::Stance(Stance::Crouching) => ("crouch", String::new()),
+        ActionDetail::Stance(Stance::Kneeling) => ("kneel", String::new()),
+        ActionDetail::Stance(Stance::Prone) => ("lie", "down".to_string()),
+        ActionDetail::Inventory(_) => ("handle", "something".to_string()),
+    }
+}
+
+// "you walk", "the goblin walks"
+fn conjugate(verb: &str, second_person: bool) -> String {
+    if second_person {
+        verb.to_string()
+    } else if verb.ends_with('s') || verb.ends_with("sh") || verb.ends_with("ch") {
+        format!("{}es", verb)
+    } else {
+        format!("{}s", verb)
+    }
+}
+
+fn sentence(parts: &[&str]) -> String {
+    let text = parts
+        .iter()
+        .filter(|p| !p.is_empty())
+        .copied()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect::<String>() + ".",
+        None => text,
+    }
+}
+
+fn action_sentence(
+    kind: EntryKind,
+    subject: &str,
+    detail: Option<&ActionDetail>,
+    target: Option<&str>,
+) -> String {
+    let you = subject == "you";
+    let Some(detail) = detail else {
+        return sentence(&[subject, &conjugate("stop", you)]);
+    };
+    let (verb, rest) = phrase(detail, target);
+    match kind {
+        EntryKind::ActionStarted => {
+            sentence(&[subject, &conjugate("begin", you), "to", verb, &rest])
+        }
+        EntryKind::ActionCompleted => sentence(&[subject, &conjugate(verb, you), &rest]),
+        EntryKind::ActionAborted => {
+            sentence(&[subject, &conjugate("stop", you), "trying to", verb, &rest])
+        }
+        _ => sentence(&[subject, "can't", verb, &rest]),
+    }
+}
+
+pub(crate) fn write_journal(
+    mut journal: ResMut<Journal>,
+    mut ev_start: EventReader<ActionStartedEvent>,
+    mut ev_complete: EventReader<ActionCompleteEvent>,
+    mut ev_abort: EventReader<ActionAbortedEvent>,
+    mut ev_invalid: EventReader<ActionInvalidatedEvent>,
+    mut ev_attack: EventReader<AttackEvent>,
+    mut ev_pickup: EventReader<ItemPickUpEvent>,
+    mut seen: Local<HashSet<Entity>>,
+    player: Query<(Entity, &Vision), With<Player>>,
+    species: Query<&Species>,
+    loci: Query<&Locus>,
+    board: Res<Board>,
+    tick: Res<TickCount>,
+) {
+    let (player, vision) = match player.get_single() {
+        Ok((entity, vision)) => (Some(entity), Some(vision)),
+        Err(_) => (None, None),
+    };
+    let position = |entity: Entity| loci.get(entity).ok().map(|l| l.position);
+    // the player knows what they do themselves, and what they can see
+    let perceives = |entity: Entity| {
+        Some(entity) == player
+            || position(entity).is_some_and(|pos| vision.is_some_and(|v| v.can_see(&pos)))
+    };
+    let mut write = |kind: EntryKind, actor: Entity, target: Option<Entity>, text: String| {
+        journal.record(Entry {
+            tick: tick.0,
+            kind,
+            actor,
+            target,
+            position: position(actor).map(|p| p.to_array()),
+            perceived: perceives(actor) || target.is_some_and(|t| Some(t) == player),
+            text,
+        });
+    };
+
+    let mut actions: Vec<(EntryKind, Entity, Option<ActionDetail>)> = vec![];
+    actions.extend(
+        ev_invalid
+            .read()
+            .map(|ev| (EntryKind::ActionInvalidated, ev.entity, Some(ev.detail))),
+    );
+    actions.extend(
+        ev_start
+            .read()
+            .map(|ev| (EntryKind::ActionStarted, ev.entity, Some(ev.detail))),
+    );
+    actions.extend(
+        ev_abort
+            .read()
+            .map(|ev| (EntryKind::ActionAborted, ev.entity, ev.detail)),
+    );
+    actions.extend(
+        ev_complete
+            .read()
+            .map(|ev| (EntryKind::ActionCompleted, ev.entity, Some(ev.detail))),
+    );
+
+    for (kind, entity, detail) in actions {
+        let target = detail.and_then(|d| d.target());
+        let target_name = target.map(|t| name(t, player, &species));
+        let text = action_sentence(
+            kind,
+            &name(entity, player, &species),
+            detail.as_ref(),
+            target_name.as_deref(),
+        );
+        write(kind, entity, target, text);
+    }
+
+    // whether it landed; nothing takes harm from it yet, so there is no more to say
+    for ev in ev_attack.read() {
+        let attacker = name(ev.attacker, player, &species);
+        let (verb, at) = match (ev.hit, ev.impact >= CHARGE_IMPACT) {
+            (true, true) => ("charge", ""),
+            (true, false) => ("strike", ""),
+            (false, _) => ("swing", "at"),
+        };
+        let text = sentence(&[
+            &attacker,
+            &conjugate(verb, attacker == "you"),
+            at,
+            &name(ev.target, player, &species),
+        ]);
+        write(EntryKind::Attack, ev.attacker, Some(ev.target), text);
+    }
+
+    for ev in ev_pickup.read() {
+        let owner = name(ev.owner, player, &species);
+        let text = sentence(&[&owner, &conjugate("pick", owner == "you"), "something up"]);
+        write(EntryKind::Pickup, ev.owner, Some(ev.item), text);
+    }
+
+    // anyone coming into the player's view
+    if let (Some(player), Some(vision)) = (player, vision) {
+        let visible = visible_creatures(vision, &board, player);
+        for entity in visible.difference(&seen) {
+            let text = sentence(&["you notice", &name(*entity, Some(player), &species)]);
+            write(EntryKind::Perception, player, Some(*entity), text);
+        }
+        *seen = visible;
+    }
+}
+
+#[test]
+fn test_journal_sentences_and_round_trip() {
+    let walk = ActionDetail::Move(MovementActionDetail::Walk(Dir::East));
+    assert_eq!(
+        action_sentence(EntryKind::ActionStarted, "the goblin", Some(&walk), None),
+        "The goblin begins to walk east."
+    );
+    assert_eq!(
+        action_sentence(EntryKind::ActionCompleted, "you", Some(&walk), None),
+        "You walk east."
+    );
+    assert_eq!(
+        action_sentence(EntryKind::ActionAborted, "the goblin", None, None),
+        "The goblin stops."
+    );
+
+    let mut journal = Journal::default();
+    let entry = |tick: u32, perceived: bool| Entry {
+        tick,
+        kind: EntryKind::ActionCompleted,
+        actor: Entity::from_raw(1),
+        target: None,
+        position: Some([3, 3, 0]),
+        perceived,
+        text: "You walk east.".to_string(),
+    };
+    journal.record(entry(4, true));
+    journal.record(entry(9, false));
+
+    assert_eq!(journal.perceived().count(), 1);
+    assert_eq!(journal.since(5).count(), 1);
+    assert_eq!(journal.involving(Entity::from_raw(1)).count(), 2);
+
+    let text = journal.to_ron().unwrap();
+    assert!(Journal::from_ron(&text)
+        .unwrap()
+        .entries()
+        .eq(journal.entries()));
+
+    // the oldest go first
+    for tick in 10..MAX_ENTRIES as u32 + 10 {
+        journal.record(entry(tick, false));
+    }
+    assert_eq!(journal.entries().count(), MAX_ENTRIES);
+    assert_eq!(journal.entries().next().map(|e| e.tick), Some(10));
+}
@@ -5,13 +5,18 @@ pub(crate) mod board;
 pub(crate) mod combat;
 pub(crate) mod creature;
 pub(crate) mod dice;
+pub(crate) mod gameplay;
 pub(crate) mod goblin;
 pub(crate) mod graphics;
 pub(crate) mod input;
 pub(crate) mod inventory;
+pub(crate) mod journal;
 pub(crate) mod marker_components;
 pub(crate) mod material;
 pub(crate) mod player;
+pub(crate) mod scheduler;
+#[cfg(test)]
+pub(crate) mod sim;
 pub(crate) mod time;
 pub(crate) mod typical;
 
@@ -20,22 +25,8 @@ use bevy_fps_counter::FpsCounterPlugin;
 use bevy_mod_picking::DefaultPickingPlugins;
 use bevy_turborand::prelude::RngPlugin;
 use input::PlayerInputState;
-use player::SpawnPlayerEvent;
 use typical::graphics::*;
 
-// System sets and such
-#[derive(Ord, SystemSet, Debug, Hash, PartialEq, Eq, Clone, PartialOrd)]
-enum ActionSet {
-    // Plan:
-    Assign,
-    Validate,
-    // Run:
-    Tick,
-    Apply,
-    // AwaitAnim:
-    Animate,
-}
-
 // #[derive(States, Debug, Default, Hash, Eq, Clone, PartialEq)]
 // enum AssetLoadState {
 //     #[default]
@@ -43,9 +34,6 @@ enum ActionSet {
 //     Ready,
 // }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
-struct CustomFlush;
-
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
 struct ActorBehaviour;
 
@@ -75,29 +63,14 @@ fn main() {
         // PLUGINS
         .add_plugins(FpsCounterPlugin)
         .add_plugins(RngPlugin::default())
-        .add_plugins(time::TimePlugin)
         .add_plugins(DefaultPickingPlugins)
+        .add_plugins(gameplay::GameplayPlugin)
         // RESOURCES
         .insert_resource(ClearColor(Color::BLACK))
         .init_resource::<Msaa>()
-        .init_resource::<Board>()
+        .init_resource::<board::generator::Depth>()
         // STATE
-        .add_state::<ActionSystemState>()
-        .add_state::<PlayerInputState>()
         // .add_state::<AssetLoadState>()
-        // EVENTS
-        .add_event::<SpawnPlayerEvent>()
-        .add_event::<TickEvent>()
-        .add_event::<PlayerInputRequestEvent>()
-        .add_event::<ActionInvalidatedEvent>()
-        .add_event::<ActionValidatedEvent>()
-        .add_event::<ActionPlanRequestEvent>()
-        .add_event::<ActionCompleteEvent>()
-        .add_event::<ActionAddedEvent>()
-        .add_event::<ActionStartedEvent>()
-        .add_event::<ActionAbortedEvent>()
-        .add_event::<StillWaitForAnimEvent>()
-        .add_event::<SpawnGoblinEvent>()
         //
         // SYSTEMS
         //
@@ -116,95 +89,22 @@ fn main() {
                 goblin::spawn_goblins,
                 apply_deferred,
                 graphics::spawn_player_sprite_and_2d_camera,
-                action::bootstrap,
-            )
-                .chain(),
-        )
-        //
-        // Actions
-        //
-        .configure_sets(
-            PreUpdate,
-            (
-                ActionSet::Assign,
-                ActionSet::Validate,
-                ActionSet::Tick,
-                ActionSet::Apply,
-                ActionSet::Animate,
-            )
-                .chain(),
-        )
-        //
-        .add_systems(
-            OnEnter(ActionSystemState::Plan),
-            action::plan_init_check_or_tick,
-        )
-        .add_systems(
-            PreUpdate,
-            (
-                (
-                    action::plan_init_check_or_tick.run_if(
-                        on_event::<ActionAddedEvent>().or_else(on_event::<ActionValidatedEvent>()),
-                    ),
-                    input::keybindings.run_if(in_state(PlayerInputState::Listen)),
-                    action::plan_agent_actions.run_if(on_event::<ActionPlanRequestEvent>()),
-                    apply_deferred.run_if(on_event::<ActionAddedEvent>()),
-                )
-                    .chain()
-                    .in_set(ActionSet::Assign)
-                    .run_if(in_state(ActionSystemState::Plan)),
-                (
-                    action::validation::validate_move.run_if(on_event::<ActionAddedEvent>()),
-                    // put more validations here
-                    action::handle_action_invalid.run_if(on_event::<ActionInvalidatedEvent>()),
-                    apply_deferred,
-                )
-                    .chain()
-                    .in_set(ActionSet::Validate)
-                    .run_if(in_state(ActionSystemState::Plan)),
-                (
-                    action::set_state_run,
-                    action::clock_tick,
-                    action::tick_actions,
-                    apply_deferred,
-                    action::plan_init_check_or_tick, // proceed to next tick?
-                )
-                    .chain()
-                    .in_set(ActionSet::Tick)
-                    .run_if(on_event::<TickEvent>()),
-                (
-                    action::apply_completed_action_markers,
-                    apply_deferred,
-                    action::on_success::apply_move,
-                    action::on_success::apply_attack,
-                    // ...
-                    action::set_state_await_anim,
-                    apply_deferred,
-                )
-                    .chain()
-                    .in_set(ActionSet::Apply)
-                    .run_if(on_event::<ActionCompleteEvent>()),
-            ),
-        )
-        .add_systems(
-            Update,
-            (
-                graphics::move_anim::lerp_vec3_translation,
-                // keep-alive or return to set player input
-                (action::set_state_plan, apply_deferred.in_set(CustomFlush))
-                    .chain()
-                    .run_if(not(on_event::<StillWaitForAnimEvent>())),
+                graphics::hud::spawn_command_queue_text,
+                graphics::hud::spawn_intents_text,
+                graphics::hud::spawn_clock_text,
             )
                 .chain()
-                .in_set(ActionSet::Animate)
-                .run_if(in_state(ActionSystemState::AwaitAnim)),
+                .before(gameplay::BeginPlay),
         )
-        .add_systems(Update, bevy::window::close_on_esc)
+        .add_systems(Update, (bevy::window::close_on_esc, input::time_controls))
         .add_systems(
             Update,
             (
                 graphics::torchlight::flicker_torches,
                 graphics::move_anim::animate_player_fov,
+                graphics::hud::update_command_queue_text,
+                graphics::hud::update_intents_text,
+                graphics::hud::update_clock_text,
             ),
         )
         .add_systems(
@@ -1,3 +1,6 @@
+use crate::action::command::CommandQueue;
+use crate::board::light::LightSource;
+use crate::creature::Explored;
 use crate::typical::graphics::*;
 use bevy::core_pipeline::clear_color::ClearColorConfig;
 use bevy::prelude::*;
@@ -20,6 +23,9 @@ pub(crate) struct PlayerBundle {
     player: Player,
     creature: CreatureBundle,
     character: CharacterBundle,
+    light: LightSource,
+    explored: Explored,
+    commands: CommandQueue,
 }
 
 impl Default for PlayerBundle {
@@ -43,6 +49,9 @@ impl Default for PlayerBundle {
                 },
                 ..default()
             },
+            light: LightSource::torch(),
+            explored: Explored::default(),
+            commands: CommandQueue::default(),
         }
     }
 }
@@ -0,0 +1,368 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::timeline::Timeline;
+use crate::board::light::LightSource;
+use crate::creature::{Condition, ConditionList, Stamina};
+use crate::gameplay::ActionSet;
+use crate::typical::*;
+
+// Scheduler
+//
+// things which happen in their own good time, whether or not anyone's doing
+// anything: a wound bleeding, poison working, a torch guttering out. Each is
+// booked for a tick, and goes off once the clock reaches it - in the order
+// booked, if several fall due together. Booking hands back a ticket, which
+// is all it takes to call it off.
+//
+// the whole queue is plain data, so it goes into a save along with everything
+// else.
+
+// a pulse of blood or poison every few seconds, for as long as it lasts
+const BLEED_INTERVAL: Dur = Dur::seconds(5);
+const BLEED_LOSS: f32 = 0.05; // litres
+const POISON_INTERVAL: Dur = Dur::seconds(10);
+const POISON_STAMINA: u32 = 2;
+// a torch is good for an hour or so
+const TORCH_LIFE: Dur = Dur::hours(1);
+
+// when it's due, and where it came in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub(crate) struct Ticket {
+    pub(crate) tick: u32,
+    seq: u64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Scheduled {
+    Bleed(Entity),
+    Poison(Entity),
+    TorchBurnsOut(Entity),
+    // more goblins, turning up where they're told
+    Reinforcements { at: [i32; 3], count: u8 },
+}
+
+impl Scheduled {
+    pub(crate) fn entity(&self) -> Option<Entity> {
+        match self {
+            Scheduled::Bleed(e) | Scheduled::Poison(e) | Scheduled::TorchBurnsOut(e) => Some(*e),
+            Scheduled::Reinforcements { .. } => None,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Scheduler {
+    queue: BTreeMap<Ticket, Scheduled>,
+    next_seq: u64,
+}
+
+#[allow(dead_code)]
+impl Scheduler {
+    pub(crate) fn at(&mut self, tick: u32, what: Scheduled) -> Ticket {
+        let ticket = Ticket {
+            tick,
+            seq: self.next_seq,
+        };
+        self.next_seq += 1;
+        self.queue.insert(ticket, what);
+        ticket
+    }
+
+    pub(crate) fn after(&mut self, now: u32, wait: Dur, what: Scheduled) -> Ticket {
+        self.at(wait.after(now), what)
+    }
+
+    pub(crate) fn cancel(&mut self, ticket: Ticket) -> Option<Scheduled> {
+        self.queue.remove(&ticket)
+    }
+
+    // everything booked for someone who's no longer around
+    pub(crate) fn cancel_for(&mut self, entity: Entity) {
+        self.queue.retain(|_, what| what.entity() != Some(entity));
+    }
+
+    pub(crate) fn is_scheduled(&self, what: &Scheduled) -> bool {
+        self.queue.values().any(|w| w == what)
+    }
+
+    pub(crate) fn next_due(&self) -> Option<u32> {
+        self.queue.keys().next().map(|ticket| ticket.tick)
+    }
+
+    // everything due by now, soonest (then first booked) first
+    pub(crate) fn drain_due(&mut self, now: u32) -> Vec<(Ticket, Scheduled)> {
+        let mut due = vec![];
+        while let Some(entry) = self.queue.first_entry() {
+            if entry.key().tick > now {
+                break;
+            }
+            let ticket = *entry.key();
+            due.push((ticket, entry.remove()));
+        }
+        due
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub(crate) fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub(crate) fn from_ron(text: &str) -> Result<Scheduler, ron::error::SpannedError> {
+        ron::from_str(text)
+    }
+}
+
+// something booked has come due
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct DueEvent {
+    pub(crate) ticket: Ticket,
+    pub(crate) what: Scheduled,
+}
+
+pub(crate) struct SchedulerPlugin;
+
+impl Plugin for SchedulerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Scheduler>()
+            .add_event::<DueEvent>()
+            .add_systems(
+                PreUpdate,
+                (
+                    (bleed, poison, burn_out, reinforce, unsettle_monitors)
+                        .run_if(on_event::<DueEvent>()),
+                    start_pulses,
+                    light_torches,
+                )
+                    .after(ActionSet::Tick)
+                    .before(ActionSet::Apply),
+            );
+    }
+}
+
+// in the Tick set, once the clock has moved on
+pub(crate) fn drain_scheduler(
+    mut scheduler: ResMut<Scheduler>,
+    mut ev_due: EventWriter<DueEvent>,
+    tick: Res<TickCount>,
+) {
+    for (ticket, what) in scheduler.drain_due(tick.0) {
+        info!("due at {}: {:?}", ticket.tick, what);
+        ev_due.send(DueEvent { ticket, what });
+    }
+}
+
+// whatever went off may have changed things under someone's monitors. Not
+// in drain_scheduler: that's before the effects land, and monitors looked at
+// then would see the world as it was.
+fn unsettle_monitors(mut ev_due: EventReader<DueEvent>, mut timeline: ResMut<Timeline>) {
+    if ev_due.read().count() > 0 {
+        timeline.world_changed();
+    }
+}
+
+// book the first pulse of anything newly bleeding or poisoned
+fn start_pulses(
+    query: Query<(Entity, &ConditionList), Changed<ConditionList>>,
+    mut scheduler: ResMut<Scheduler>,
+    tick: Res<TickCount>,
+) {
+    for (entity, conditions) in query.iter() {
+        let pulses = [
+            (
+                Condition::Bleeding,
+                Scheduled::Bleed(entity),
+                BLEED_INTERVAL,
+            ),
+            (
+                Condition::Poisoned,
+                Scheduled::Poison(entity),
+                POISON_INTERVAL,
+            ),
+        ];
+        for (condition, pulse, interval) in pulses {
+            if conditions.has(condition) && !scheduler.is_scheduled(&pulse) {
+                scheduler.after(tick.0, interval, pulse);
+            }
+        }
+    }
+}
+
+fn bleed(
+    mut ev_due: EventReader<DueEvent>,
+    mut query: Query<(&ConditionList, &mut NeedList)>,
+    mut scheduler: ResMut<Scheduler>,
+    tick: Res<TickCount>,
+) {
+    for ev in ev_due.read() {
+        let Scheduled::Bleed(entity) = ev.what else {
+            continue;
+        };
+        let Ok((conditions, mut needs)) = query.get_mut(entity) else {
+            continue;
+        };
+        if !conditions.has(Condition::Bleeding) {
+            continue;
+        }
+        needs.blood = (needs.blood - BLEED_LOSS).max(0.0);
+        scheduler.after(tick.0, BLEED_INTERVAL, ev.what);
+    }
+}
+
+fn poison(
+    mut ev_due: EventReader<DueEvent>,
+    mut query: Query<(&ConditionList, &mut Stamina)>,
+    mut scheduler: ResMut<Scheduler>,
+    tick: Res<TickCount>,
+) {
+    for ev in ev_due.read() {
+        let Scheduled::Poison(entity) = ev.what else {
+            continue;
+        };
+        let Ok((conditions, mut stamina)) = query.get_mut(entity) else {
+            continue;
+        };
+        if !conditions.has(Condition::Poisoned) {
+            continue;
+        }
+        stamina.spend(POISON_STAMINA);
+        scheduler.after(tick.0, POISON_INTERVAL, ev.what);
+    }
+}
+
+// every new torch starts burning down
+fn light_torches(
+    query: Query<Entity, Added<LightSource>>,
+    mut scheduler: ResMut<Scheduler>,
+    tick: Res<TickCount>,
+) {
+    for entity in query.iter() {
+        scheduler.after(tick.0, TORCH_LIFE, Scheduled::TorchBurnsOut(entity));
+    }
+}
+
+fn burn_out(mut ev_due: EventReader<DueEvent>, mut commands: Commands) {
+    for ev in ev_due.read() {
+        if let Scheduled::TorchBurnsOut(entity) = ev.what {
+            info!("{:?}'s torch gutters out", entity);
+            if let Some(mut entity) = commands.get_entity(entity) {
+                entity.remove::<LightSource>();
+            }
+        }
+    }
+}
+
+fn reinforce(mut ev_due: EventReader<DueEvent>, mut ev_spawn: EventWriter<SpawnGoblinEvent>) {
+    for ev in ev_due.read() {
+        if let Scheduled::Reinforcements { at, count } = ev.what {
+            for _ in 0..count {
+                ev_spawn.send(SpawnGoblinEvent(IVec3::from_array(at)));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_due_in_order_cancelled_and_saved() {
+    let e = Entity::from_raw(7);
+    let mut scheduler = Scheduler::default();
+    scheduler.at(300, Scheduled::TorchBurnsOut(e));
+    let bleed = scheduler.at(50, Scheduled::Bleed(e));
+    scheduler.at(50, Scheduled::Poison(e));
+    let later = scheduler.after(100, Dur::seconds(90), Scheduled::Bleed(e));
+
+    assert_eq!(scheduler.next_due(), Some(50));
+    assert_eq!(scheduler.cancel(later), Some(Scheduled::Bleed(e)));
+    assert_eq!(scheduler.cancel(later), None);
+
+    // saved and loaded, nothing's lost - including the order
+    let mut scheduler = Scheduler::from_ron(&scheduler.to_ron().unwrap()).unwrap();
+    assert_eq!(scheduler.len(), 3);
+
+    let due = scheduler.drain_due(60);
+    assert_eq!(
+        due.iter().map(|(_, what)| *what).collect::<Vec<_>>(),
+        [Scheduled::Bleed(e), Scheduled::Poison(e)]
+    );
+    assert_eq!(due[0].0, bleed);
+    assert!(scheduler.drain_due(299).is_empty());
+
+    scheduler.cancel_for(e);
+    assert!(scheduler.is_empty());
+}
+
+#[test]
+fn test_monitors_hear_about_scheduled_events() {
+    use crate::action::command::Command;
+    use crate::action::kinds::hold::{Response, Trigger};
+    use crate::action::time_advance::{TimeAdvance, TimeAdvanceMode};
+    use crate::action::{ActionStatus, ActorAction};
+    use crate::sim::{Recorded, Sim};
+
+    // whatever was booked, it leaves the player reeling
+    fn stun(mut ev_due: EventReader<DueEvent>, mut query: Query<&mut ConditionList, With<Player>>) {
+        if ev_due.read().count() > 0 {
+            for mut conditions in query.iter_mut() {
+                conditions.add(Condition::Stunned);
+            }
+        }
+    }
+
+    for mode in [
+        TimeAdvanceMode::PerTick,
+        TimeAdvanceMode::NextEvent,
+        TimeAdvanceMode::RealTime,
+    ] {
+        let mut sim = Sim::default();
+        sim.record::<ActionInterruptedEvent>();
+        sim.app.add_systems(
+            PreUpdate,
+            stun.after(ActionSet::Tick).before(ActionSet::Apply),
+        );
+        let mut advance = sim.app.world.resource_mut::<TimeAdvance>();
+        advance.mode = mode;
+        // so real time doesn't take all day
+        advance.ticks_per_second = 1000.0;
+
+        // braced for something which never comes, for long after the booking
+        let player = sim.spawn_player(IVec3::new(3, 3, 0));
+        let far_off = IVec3::new(40, 20, 0);
+        sim.command(
+            player,
+            Command::Hold(Trigger::TerrainChanged(far_off), Response::Strike),
+            1,
+        );
+        assert!(sim.run_until(|world| world
+            .get::<ActorAction>(player)
+            .is_some_and(|a| matches!(a.0.status, ActionStatus::Active { .. }))));
+
+        let due = sim.tick() + 10;
+        sim.app.world.resource_mut::<Scheduler>().at(
+            due,
+            Scheduled::Reinforcements {
+                at: far_off.to_array(),
+                count: 0,
+            },
+        );
+        let interrupted = sim.run_until(|world| {
+            !world
+                .resource::<Recorded<ActionInterruptedEvent>>()
+                .0
+                .is_empty()
+        });
+
+        assert!(interrupted, "{:?}", mode);
+        assert!(sim.tick() <= due + 1, "{:?}", mode);
+        let ev = &sim.recorded::<ActionInterruptedEvent>()[0];
+        assert_eq!((ev.entity, ev.phase), (player, "hold"));
+    }
+}
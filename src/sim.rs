@@ -0,0 +1,241 @@
+use bevy::input::InputPlugin;
+use bevy_turborand::prelude::RngPlugin;
+
+use crate::action::command::{Command, CommandQueue};
+use crate::action::ActorAction;
+use crate::creature::{Attributes, Stamina};
+use crate::gameplay::GameplayPlugin;
+use crate::graphics::CellCubeEntityMapper;
+use crate::player::PlayerBundle;
+use crate::typical::*;
+
+// Simulation
+//
+// the game without a window: the same gameplay plugin on an open board, with
+// a fixed seed and creatures whose attributes don't depend on a roll, so a
+// scripted run comes out the same every time. Tests spawn whoever they need,
+// line up commands, run frames until something's true and look at what
+// happened.
+
+pub(crate) const SIM_SEED: u64 = 12345;
+
+// a frame budget, so a stalled loop fails rather than hangs
+const MAX_FRAMES: u32 = 10_000;
+
+// what every simulated creature's attributes come to
+const SIM_ATTRIBUTE: u8 = 5;
+
+// everything of one kind of event sent since recording began
+#[derive(Resource, Debug)]
+pub(crate) struct Recorded<E: Event>(pub(crate) Vec<E>);
+
+fn record<E: Event + Clone>(mut events: EventReader<E>, mut recorded: ResMut<Recorded<E>>) {
+    recorded.0.extend(events.read().cloned());
+}
+
+pub(crate) struct Sim {
+    pub(crate) app: App,
+}
+
+impl Default for Sim {
+    fn default() -> Self {
+        Sim::new(SIM_SEED)
+    }
+}
+
+impl Sim {
+    pub(crate) fn new(seed: u64) -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(InputPlugin)
+            .add_plugins(RngPlugin::new().with_rng_seed(seed))
+            .add_plugins(GameplayPlugin)
+            // nothing to click on, but pointer input still looks
+            .init_resource::<CellCubeEntityMapper>();
+        Sim { app }
+    }
+
+    fn creature(position: IVec3) -> CreatureBundle {
+        let attributes = Attributes::uniform(SIM_ATTRIBUTE);
+        CreatureBundle {
+            stamina: Stamina::new(&attributes.secondary),
+            attributes,
+            locus: Locus {
+                position,
+                ..default()
+            },
+            spatial: SpatialBundle {
+                transform: Transform::from_xyz(position.x as f32, position.y as f32, 0.),
+                ..default()
+            },
+            ..default()
+        }
+    }
+
+    pub(crate) fn spawn_player(&mut self, position: IVec3) -> Entity {
+        let creature = Sim::creature(position);
+        let entity = self
+            .app
+            .world
+            .spawn(PlayerBundle::default())
+            .insert((
+                creature.attributes,
+                creature.stamina,
+                creature.locus,
+                creature.spatial,
+            ))
+            .id();
+        self.place(entity, position);
+        entity
+    }
+
+    pub(crate) fn spawn_creature(&mut self, position: IVec3, species: Species) -> Entity {
+        let entity = self
+            .app
+            .world
+            .spawn(CreatureBundle {
+                species,
+                ..Sim::creature(position)
+            })
+            .id();
+        self.place(entity, position);
+        entity
+    }
+
+    fn place(&mut self, entity: Entity, position: IVec3) {
+        self.app
+            .world
+            .resource_mut::<Board>()
+            .creature_store
+            .insert(entity, position);
+    }
+
+    // as if typed in: a count, then the command
+    pub(crate) fn command(&mut self, entity: Entity, command: Command, repeat: u32) {
+        let mut queue = self
+            .app
+            .world
+            .get_mut::<CommandQueue>(entity)
+            .expect("only the player takes commands");
+        queue.count = Some(repeat);
+        queue.push(command);
+    }
+
+    // keep every event of this kind from now on
+    pub(crate) fn record<E: Event + Clone>(&mut self) {
+        self.app
+            .insert_resource(Recorded::<E>(vec![]))
+            .add_systems(Last, record::<E>);
+    }
+
+    pub(crate) fn recorded<E: Event + Clone>(&self) -> &[E] {
+        &self.app.world.resource::<Recorded<E>>().0
+    }
+
+    // run frames until the condition holds; false if it never does
+    pub(crate) fn run_until(&mut self, done: impl Fn(&World) -> bool) -> bool {
+        for _ in 0..MAX_FRAMES {
+            self.app.update();
+            if done(&self.app.world) {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub(crate) fn run_ticks(&mut self, ticks: u32) -> bool {
+        let until = self.tick() + ticks;
+        self.run_until(|world| world.resource::<TickCount>().0 >= until)
+    }
+
+    pub(crate) fn tick(&self) -> u32 {
+        self.app.world.resource::<TickCount>().0
+    }
+
+    pub(crate) fn position(&self, entity: Entity) -> IVec3 {
+        self.app.world.get::<Locus>(entity).unwrap().position
+    }
+
+    // nothing left to do, and nothing under way
+    pub(crate) fn is_idle(world: &World, entity: Entity) -> bool {
+        world.get::<ActorAction>(entity).is_none()
+            && world.get::<Actor>(entity).unwrap().queue.is_empty()
+            && world
+                .get::<CommandQueue>(entity)
+                .map_or(true, |q| q.is_empty())
+    }
+}
+
+#[test]
+fn test_player_walks_goblin_waits() {
+    let mut sim = Sim::default();
+    sim.record::<ActionCompleteEvent>();
+
+    let start = IVec3::new(3, 3, 0);
+    let player = sim.spawn_player(start);
+    // too far off to notice anyone
+    let goblin = sim.spawn_creature(IVec3::new(40, 20, 0), Species::Goblin);
+
+    sim.command(player, Command::Walk(Dir::East), 3);
+    let walked = sim.run_until(|world| {
+        let player_done = Sim::is_idle(world, player);
+        player_done && world.get::<Locus>(player).unwrap().position != start
+    });
+
+    assert!(walked);
+    assert_eq!(sim.position(player), start + IVec3::new(3, 0, 0));
+    assert_eq!(sim.position(goblin), IVec3::new(40, 20, 0));
+    assert!(sim.tick() > 0);
+
+    let completed = sim.recorded::<ActionCompleteEvent>();
+    assert_eq!(completed.iter().filter(|ev| ev.entity == player).count(), 3);
+    // the goblin stood about, one wait after another
+    assert!(completed.iter().any(|ev| ev.entity == goblin));
+}
+
+#[test]
+fn test_same_seed_same_run() {
+    let run = || {
+        let mut sim = Sim::default();
+        let player = sim.spawn_player(IVec3::new(3, 3, 0));
+        sim.spawn_creature(IVec3::new(8, 3, 0), Species::Goblin);
+        sim.command(player, Command::Walk(Dir::East), 2);
+        sim.run_until(|world| Sim::is_idle(world, player));
+        (sim.tick(), sim.position(player))
+    };
+
+    assert_eq!(run(), run());
+}
+
+// a crowd, to see the action loop keeps up: run with
+//   cargo test --release bench_thousand_actors -- --ignored --nocapture
+#[test]
+#[ignore]
+fn bench_thousand_actors() {
+    const ACTORS: usize = 1_000;
+    const TICKS: u32 = 200;
+
+    let mut sim = Sim::default();
+    let player = sim.spawn_player(IVec3::ZERO);
+    let cells = (0..BOARD_SIZE_Y)
+        .flat_map(|y| (0..BOARD_SIZE_X).map(move |x| IVec3::new(x, y, 0)))
+        .skip(1)
+        .take(ACTORS);
+    for position in cells {
+        sim.spawn_creature(position, Species::Goblin);
+    }
+    // standing about keeps the clock going for everyone else
+    sim.command(player, Command::Wait(Dur::ticks(TICKS * 2)), 1);
+
+    let started = std::time::Instant::now();
+    assert!(sim.run_ticks(TICKS));
+    let elapsed = started.elapsed();
+
+    println!(
+        "{} actors, {} ticks in {:.2?}: {:.1} ticks/s",
+        ACTORS,
+        TICKS,
+        elapsed,
+        TICKS as f64 / elapsed.as_secs_f64()
+    );
+}
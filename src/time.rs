@@ -1,26 +1,22 @@
-use bevy::prelude::{App, Component, Plugin, Resource};
-#[allow(unused_imports)]
-use bevy::utils::Duration; // TODO
+use bevy::prelude::{App, Component, Plugin, PostUpdate, Res, ResMut, Resource};
+use serde::{Deserialize, Serialize};
 use std::convert::From;
-
-/* WARN / TODO should probably use this since it exists:
-
-https://docs.rs/bevy/latest/bevy/utils/struct.Dur.html
-
-painful to refactor, but .. less so now than later ..
-also collisions with Dur and Direction suck, I should just rename them
-
-*/
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Sub};
+use std::str::FromStr;
 
 // at 10 ticks / second, a u32 is enough for 13 years worth of game time.
-// use u32 for everything to avoid casting.
+// use u32 for everything to avoid casting - a point in time is a tick count,
+// and a stretch of it is a Dur.
 
 #[derive(Default)]
 pub(crate) struct TimePlugin;
 
 impl Plugin for TimePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(TickCount(0));
+        app.insert_resource(TickCount(0))
+            .init_resource::<Clock>()
+            .add_systems(PostUpdate, update_clock);
     }
 }
 
@@ -36,40 +32,22 @@ pub(crate) enum Unit {
     Tick = 1,
     Second = 10,
     Minute = 600,
-    Hour = 3600,
+    Hour = 36_000,
     Day = 864_000,
     Week = 6_048_000,
     Year = 315_360_000,
 }
 
-#[derive(Copy, Clone, Debug, Component, Eq)]
-struct Dur {
-    unit: Unit,
-    value: u32,
-}
-
-impl Dur {
-    pub fn as_u32(&self) -> u32 {
-        Into::<u32>::into(*self)
-    }
-}
-
-impl PartialEq for Dur {
-    fn eq(&self, other: &Dur) -> bool {
-        self.as_u32().eq(&other.as_u32())
-    }
-}
-impl PartialOrd for Dur {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Dur {
-    fn cmp(&self, other: &Dur) -> std::cmp::Ordering {
-        self.as_u32().cmp(&other.as_u32())
-    }
-}
+// biggest first, as they're written
+const UNITS: [Unit; 7] = [
+    Unit::Year,
+    Unit::Week,
+    Unit::Day,
+    Unit::Hour,
+    Unit::Minute,
+    Unit::Second,
+    Unit::Tick,
+];
 
 // units of time - conversions to u32 (tick)
 #[allow(dead_code)]
@@ -97,6 +75,207 @@ impl Unit {
     pub fn years(years: u32) -> u32 {
         Unit::Year as u32 * years
     }
+
+    fn suffix(&self) -> char {
+        match self {
+            Unit::Tick => 't',
+            Unit::Second => 's',
+            Unit::Minute => 'm',
+            Unit::Hour => 'h',
+            Unit::Day => 'd',
+            Unit::Week => 'w',
+            Unit::Year => 'y',
+        }
+    }
+}
+
+// Dur
+//
+// how long something takes, lasts or waits, in ticks. Written the way anyone
+// would - "2m30s", "1h", "5t" for half a second - in data files and on
+// screen. Adding or scaling one never wraps: it just gets very long.
+
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct Dur(u32);
+
+#[allow(dead_code)]
+impl Dur {
+    pub(crate) const ZERO: Dur = Dur(0);
+
+    pub(crate) const fn ticks(ticks: u32) -> Dur {
+        Dur(ticks)
+    }
+
+    pub(crate) const fn of(value: u32, unit: Unit) -> Dur {
+        Dur(value.saturating_mul(unit as u32))
+    }
+
+    pub(crate) const fn seconds(seconds: u32) -> Dur {
+        Dur::of(seconds, Unit::Second)
+    }
+
+    pub(crate) const fn minutes(minutes: u32) -> Dur {
+        Dur::of(minutes, Unit::Minute)
+    }
+
+    pub(crate) const fn hours(hours: u32) -> Dur {
+        Dur::of(hours, Unit::Hour)
+    }
+
+    pub(crate) const fn days(days: u32) -> Dur {
+        Dur::of(days, Unit::Day)
+    }
+
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    // the tick this long after another
+    pub(crate) fn after(&self, tick: u32) -> u32 {
+        tick.saturating_add(self.0)
+    }
+
+    // how long from one tick to another; nothing, if it's already past
+    pub(crate) fn between(from: u32, to: u32) -> Dur {
+        Dur(to.saturating_sub(from))
+    }
+}
+
+impl Add for Dur {
+    type Output = Dur;
+
+    fn add(self, other: Dur) -> Dur {
+        Dur(self.0.saturating_add(other.0))
+    }
+}
+
+impl AddAssign for Dur {
+    fn add_assign(&mut self, other: Dur) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Dur {
+    type Output = Dur;
+
+    fn sub(self, other: Dur) -> Dur {
+        Dur(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Mul<u32> for Dur {
+    type Output = Dur;
+
+    fn mul(self, times: u32) -> Dur {
+        Dur(self.0.saturating_mul(times))
+    }
+}
+
+impl From<Dur> for u32 {
+    fn from(duration: Dur) -> u32 {
+        duration.0
+    }
+}
+
+// biggest units first, leaving out any with nothing in them
+impl fmt::Display for Dur {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0s");
+        }
+        let mut rest = self.0;
+        for unit in UNITS {
+            let value = rest / unit as u32;
+            if value > 0 {
+                write!(f, "{}{}", value, unit.suffix())?;
+            }
+            rest %= unit as u32;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ParseDurError {
+    Empty,
+    // a number with no unit after it, or a unit with no number
+    Incomplete,
+    UnknownUnit(char),
+    TooLong,
+}
+
+impl fmt::Display for ParseDurError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseDurError::Empty => write!(f, "no duration given"),
+            ParseDurError::Incomplete => write!(f, "every number needs a unit, like 2m30s"),
+            ParseDurError::UnknownUnit(c) => write!(f, "'{}' isn't a unit of time", c),
+            ParseDurError::TooLong => write!(f, "longer than the game can count"),
+        }
+    }
+}
+
+impl std::error::Error for ParseDurError {}
+
+// any mix of units, in any order, with or without spaces: "1h 30m", "90s", "5t"
+impl FromStr for Dur {
+    type Err = ParseDurError;
+
+    fn from_str(text: &str) -> Result<Dur, ParseDurError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(ParseDurError::Empty);
+        }
+
+        let mut total: u32 = 0;
+        let mut value: Option<u32> = None;
+        for c in text.chars().filter(|c| !c.is_whitespace()) {
+            if let Some(digit) = c.to_digit(10) {
+                let v = value.unwrap_or(0);
+                value = Some(
+                    v.checked_mul(10)
+                        .and_then(|v| v.checked_add(digit))
+                        .ok_or(ParseDurError::TooLong)?,
+                );
+                continue;
+            }
+            let unit = UNITS
+                .into_iter()
+                .find(|unit| unit.suffix() == c)
+                .ok_or(ParseDurError::UnknownUnit(c))?;
+            let ticks = value
+                .take()
+                .ok_or(ParseDurError::Incomplete)?
+                .checked_mul(unit as u32)
+                .ok_or(ParseDurError::TooLong)?;
+            total = total.checked_add(ticks).ok_or(ParseDurError::TooLong)?;
+        }
+        if value.is_some() {
+            return Err(ParseDurError::Incomplete);
+        }
+        Ok(Dur(total))
+    }
+}
+
+impl TryFrom<String> for Dur {
+    type Error = ParseDurError;
+
+    fn try_from(text: String) -> Result<Dur, ParseDurError> {
+        text.parse()
+    }
+}
+
+impl From<Dur> for String {
+    fn from(duration: Dur) -> String {
+        duration.to_string()
+    }
 }
 
 #[derive(Resource, Component, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
@@ -112,16 +291,17 @@ impl TickCount {
         self.advance(1)
     }
 
+    // the clock stops at the end of time, rather than starting over
     pub fn advance(&mut self, t: u32) {
-        self.0 = self.0.checked_add(t).unwrap_or(0);
+        self.0 = self.0.saturating_add(t);
     }
 
-    // pub fn advance_by(&mut self, duration: Dur) {
-    //     self.advance(duration.into());
-    // }
+    pub fn advance_by(&mut self, duration: Dur) {
+        self.advance(duration.as_u32());
+    }
 
     pub fn add(&self, other: u32) -> u32 {
-        self.0 + other
+        self.0.saturating_add(other)
     }
 
     pub fn as_u32(&self) -> u32 {
@@ -140,20 +320,127 @@ impl From<TickCount> for u32 {
         time.0
     }
 }
-impl From<Dur> for u32 {
-    fn from(duration: Dur) -> u32 {
-        duration.unit as u32 * duration.value
+
+// Calendar
+//
+// the old northern reckoning: twelve months of thirty days, named for what
+// the weather's doing, and five days of Jol at the turn of the year to make
+// up the difference. Weeks run on regardless of months or years.
+//
+// the sun keeps the same hours across a season - long summer evenings,
+// short winter days - and brightens and fades over an hour either side of
+// rising and setting.
+
+const DAYS_IN_MONTH: u32 = 30;
+const DAYS_IN_WEEK: u32 = 7;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub(crate) enum Month {
+    #[default]
+    Thorri,
+    Goa,
+    Einmanudur,
+    Harpa,
+    Skerpla,
+    Solmanudur,
+    Heyannir,
+    Tvimanudur,
+    Haustmanudur,
+    Gormanudur,
+    Ylir,
+    Morsugur,
+    // the odd five days
+    Jol,
+}
+
+const MONTHS: [Month; 13] = [
+    Month::Thorri,
+    Month::Goa,
+    Month::Einmanudur,
+    Month::Harpa,
+    Month::Skerpla,
+    Month::Solmanudur,
+    Month::Heyannir,
+    Month::Tvimanudur,
+    Month::Haustmanudur,
+    Month::Gormanudur,
+    Month::Ylir,
+    Month::Morsugur,
+    Month::Jol,
+];
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub(crate) enum Weekday {
+    #[default]
+    Sunnudagur,
+    Manadagur,
+    Tysdagur,
+    Odinsdagur,
+    Thorsdagur,
+    Frjadagur,
+    Laugardagur,
+}
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Sunnudagur,
+    Weekday::Manadagur,
+    Weekday::Tysdagur,
+    Weekday::Odinsdagur,
+    Weekday::Thorsdagur,
+    Weekday::Frjadagur,
+    Weekday::Laugardagur,
+];
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub(crate) enum Season {
+    #[default]
+    Winter,
+    Spring,
+    Summer,
+    Autumn,
+}
+
+impl Month {
+    pub(crate) fn season(&self) -> Season {
+        match self {
+            Month::Morsugur | Month::Jol | Month::Thorri | Month::Goa => Season::Winter,
+            Month::Einmanudur | Month::Harpa | Month::Skerpla => Season::Spring,
+            Month::Solmanudur | Month::Heyannir | Month::Tvimanudur => Season::Summer,
+            Month::Haustmanudur | Month::Gormanudur | Month::Ylir => Season::Autumn,
+        }
     }
 }
 
-#[derive(Resource, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
+impl Season {
+    // sunrise and sunset, to the hour
+    pub(crate) fn sun(&self) -> (u32, u32) {
+        match self {
+            Season::Winter => (9, 16),
+            Season::Spring => (6, 19),
+            Season::Summer => (4, 22),
+            Season::Autumn => (7, 18),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub(crate) enum DayPhase {
+    Night,
+    Dawn,
+    Day,
+    Dusk,
+}
+
+#[derive(Resource, Component, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Clone, Copy)]
 pub struct Clock {
     time: TickCount,
 
     year: u32,
     day_of_year: u32,
-    week: u32,
-    weekday: u32,
+    month: Month,
+    day_of_month: u32,
+    week: u32, // of the year
+    weekday: Weekday,
     hour: u32,
     minute: u32,
     second: u32,
@@ -163,37 +450,177 @@ pub struct Clock {
 #[allow(dead_code)]
 impl Clock {
     pub(crate) fn new(time: TickCount) -> Clock {
-        let mut tick = time.0;
+        let tick = time.0;
 
         let year = tick / Unit::Year as u32;
-        tick -= year * Unit::Year as u32;
-        let day_of_year = tick / Unit::Day as u32;
-
-        let week = tick / Unit::Week as u32;
-        tick -= week * Unit::Week as u32;
-
-        let weekday = tick / Unit::Day as u32;
-        tick -= weekday * Unit::Day as u32;
-
-        let hour = tick / Unit::Hour as u32;
-        tick -= hour * Unit::Hour as u32;
+        let day_of_year = tick % Unit::Year as u32 / Unit::Day as u32;
+        // weeks don't care where the year starts
+        let weekday = WEEKDAYS[(tick / Unit::Day as u32 % DAYS_IN_WEEK) as usize];
 
-        let minute = tick / Unit::Minute as u32;
-        tick -= minute * Unit::Minute as u32;
-
-        let second = tick / Unit::Second as u32;
-        tick -= second * Unit::Second as u32;
+        let of_day = tick % Unit::Day as u32;
 
         Clock {
-            time: TickCount(tick),
+            time,
             year,
             day_of_year,
-            week,
+            month: MONTHS[(day_of_year / DAYS_IN_MONTH) as usize],
+            day_of_month: day_of_year % DAYS_IN_MONTH,
+            week: day_of_year / DAYS_IN_WEEK,
             weekday,
-            hour,
-            minute,
-            second,
-            tick,
+            hour: of_day / Unit::Hour as u32,
+            minute: of_day % Unit::Hour as u32 / Unit::Minute as u32,
+            second: of_day % Unit::Minute as u32 / Unit::Second as u32,
+            tick: of_day % Unit::Second as u32,
         }
     }
+
+    pub(crate) fn time(&self) -> TickCount {
+        self.time
+    }
+
+    pub(crate) fn year(&self) -> u32 {
+        self.year
+    }
+
+    pub(crate) fn month(&self) -> Month {
+        self.month
+    }
+
+    // counting from zero, like everything else
+    pub(crate) fn day_of_month(&self) -> u32 {
+        self.day_of_month
+    }
+
+    pub(crate) fn week(&self) -> u32 {
+        self.week
+    }
+
+    pub(crate) fn weekday(&self) -> Weekday {
+        self.weekday
+    }
+
+    pub(crate) fn season(&self) -> Season {
+        self.month.season()
+    }
+
+    pub(crate) fn hour(&self) -> u32 {
+        self.hour
+    }
+
+    pub(crate) fn minute(&self) -> u32 {
+        self.minute
+    }
+
+    fn hour_of_day(&self) -> f32 {
+        self.hour as f32 + self.minute as f32 / 60.
+    }
+
+    // 0 in the dark, 1 in broad daylight
+    pub(crate) fn daylight(&self) -> f32 {
+        let (rise, set) = self.season().sun();
+        let hour = self.hour_of_day();
+        let dawn = (hour - (rise as f32 - 1.)) / 2.;
+        let dusk = ((set as f32 + 1.) - hour) / 2.;
+        dawn.clamp(0., 1.) * dusk.clamp(0., 1.)
+    }
+
+    pub(crate) fn day_phase(&self) -> DayPhase {
+        let (rise, set) = self.season().sun();
+        match self.hour_of_day() {
+            h if (h - rise as f32).abs() < 1. => DayPhase::Dawn,
+            h if (h - set as f32).abs() < 1. => DayPhase::Dusk,
+            h if h > rise as f32 && h < set as f32 => DayPhase::Day,
+            _ => DayPhase::Night,
+        }
+    }
+
+    pub(crate) fn is_night(&self) -> bool {
+        self.day_phase() == DayPhase::Night
+    }
+}
+
+// "Frjadagur 6 Harpa, year 2 - 14:05"
+impl std::fmt::Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} {} {:?}, year {} - {:02}:{:02}",
+            self.weekday,
+            self.day_of_month + 1,
+            self.month,
+            self.year + 1,
+            self.hour,
+            self.minute
+        )
+    }
+}
+
+// kept in step with the tick count, for anything that wants the time of day
+pub(crate) fn update_clock(tick: Res<TickCount>, mut clock: ResMut<Clock>) {
+    *clock = tick.as_clock();
+}
+
+#[test]
+fn test_clock_fields() {
+    let clock = Clock::new(TickCount(
+        Unit::years(1) + Unit::days(95) + Unit::hours(14) + Unit::minutes(5) + Unit::seconds(7) + 3,
+    ));
+    assert_eq!(clock.year(), 1);
+    assert_eq!(clock.day_of_year, 95);
+    assert_eq!(clock.month(), Month::Harpa);
+    assert_eq!(clock.day_of_month(), 5);
+    assert_eq!(clock.week(), 13);
+    // 365 + 95 days in
+    assert_eq!(clock.weekday(), WEEKDAYS[(460 % 7) as usize]);
+    assert_eq!(
+        (clock.hour(), clock.minute(), clock.second, clock.tick),
+        (14, 5, 7, 3)
+    );
+    assert_eq!(clock.time().0 % Unit::Second as u32, 3);
+    assert_eq!(clock.season(), Season::Spring);
+    assert_eq!(clock.to_string(), "Frjadagur 6 Harpa, year 2 - 14:05");
+
+    // the last day of the year
+    let clock = Clock::new(TickCount(Unit::days(364)));
+    assert_eq!((clock.month(), clock.day_of_month()), (Month::Jol, 4));
+}
+
+#[test]
+fn test_day_and_night() {
+    let at = |days: u32, hours: u32| Clock::new(TickCount(Unit::days(days) + Unit::hours(hours)));
+
+    // midwinter: dark by suppertime
+    assert!(at(10, 2).is_night());
+    assert_eq!(at(10, 9).day_phase(), DayPhase::Dawn);
+    assert_eq!(at(10, 12).daylight(), 1.0);
+    assert!(at(10, 18).is_night());
+    // midsummer: still light
+    assert_eq!(at(190, 20).day_phase(), DayPhase::Day);
+    assert_eq!(at(190, 0).daylight(), 0.0);
+    assert!(at(190, 4).daylight() > 0.0);
+}
+
+#[test]
+fn test_durations_read_and_written() {
+    let d: Dur = "2m30s".parse().unwrap();
+    assert_eq!(d, Dur::minutes(2) + Dur::seconds(30));
+    assert_eq!(d.to_string(), "2m30s");
+    assert_eq!("1h 5t".parse::<Dur>(), Ok(Dur::hours(1) + Dur::ticks(5)));
+    assert_eq!("90s".parse::<Dur>().unwrap().to_string(), "1m30s");
+    assert_eq!(Dur::ZERO.to_string(), "0s");
+
+    assert_eq!("".parse::<Dur>(), Err(ParseDurError::Empty));
+    assert_eq!("30".parse::<Dur>(), Err(ParseDurError::Incomplete));
+    assert_eq!("3q".parse::<Dur>(), Err(ParseDurError::UnknownUnit('q')));
+    assert_eq!("99y".parse::<Dur>(), Err(ParseDurError::TooLong));
+
+    // in data files, as written
+    assert_eq!(ron::to_string(&d).unwrap(), "\"2m30s\"");
+    assert_eq!(ron::from_str::<Dur>("\"1d\"").unwrap(), Dur::days(1));
+
+    // the end of time is as far as it goes
+    let mut tick = TickCount(u32::MAX - 1);
+    tick.advance_by(Dur::hours(1));
+    assert_eq!(tick.0, u32::MAX);
+    assert_eq!(Dur::days(1) * u32::MAX, Dur::ticks(u32::MAX));
 }
@@ -20,12 +20,12 @@ pub(crate) use crate::board::{
 pub(crate) use crate::creature::anatomy::humanoid::Location;
 pub(crate) use crate::creature::{
     APSymmetry, AbilityList, Character, CharacterBundle, CharacterLevel, Creature, CreatureBundle,
-    CreatureSize, Gender, Locus, NeedList, Pace, Side, SkillList, Stance,
+    CreatureSize, Gender, Locus, NeedList, Pace, Side, SkillList, Stance, Vision,
 };
 pub(crate) use crate::dice::Dice;
 pub(crate) use crate::material::{Species, Substance};
 pub(crate) use crate::player::Player;
-pub(crate) use crate::time::{Clock, TickCount, Unit};
+pub(crate) use crate::time::{Clock, Dur, TickCount, Unit};
 
 pub use bevy::core::{FrameCount, FrameCountPlugin};
 pub use bevy::core_pipeline::clear_color::ClearColorConfig;